@@ -1,9 +1,11 @@
 use std::io::stdout;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
+use std::thread;
 use std::time::{Duration, Instant};
 
 use anyhow::Result;
-use crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use crossterm::event::{self, Event as CEvent, KeyCode, KeyEvent, KeyModifiers};
 use crossterm::execute;
 use crossterm::terminal::{
     disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
@@ -12,14 +14,54 @@ use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
 
 use crate::cli::Cli;
+use crate::diagnostics::LogBuffer;
 use crate::git;
+use crate::live::LiveServer;
+use crate::narrative;
 use crate::recording::EventLogger;
 use crate::renderer;
 use crate::scanner::ChangeTracker;
 use crate::server;
+use crate::state::EventType;
 use crate::statistics::StatisticsTracker;
 use crate::watcher::{FileWatcher, WatchEvent};
 
+/// How often the tick source fires when the filesystem is otherwise quiet,
+/// guaranteeing a steady redraw cadence independent of file activity.
+const TICK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How often the git-state source re-reads branch/HEAD/status for the root
+/// and each worktree.
+const GIT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often per-file git status (`git status --porcelain`) is refreshed.
+/// Coarser than `GIT_POLL_INTERVAL` since it walks the whole working tree
+/// on every run rather than just reading a ref.
+const GIT_STATUS_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Recordings larger than this are served straight from disk over HTTP
+/// with byte-range support instead of being inlined into the viewer URL --
+/// past this size a `#data=...` fragment risks blowing past practical URL
+/// length limits and forces the browser to hold the whole decompressed
+/// session in memory before it can render anything.
+const LARGE_RECORDING_BYTES: u64 = 2 * 1024 * 1024;
+
+/// One item from any of the run loop's input sources (keyboard, file
+/// watcher, clock, git polling, ...), fanned into a single bounded channel
+/// so the loop can block on a single `recv()` instead of polling each
+/// source in turn.
+enum Event {
+    Key(KeyEvent),
+    FileChanged(PathBuf),
+    WatchError(String),
+    Tick,
+    /// A repository's branch or HEAD commit changed since the last poll.
+    GitInfo(PathBuf, git::GitState),
+    /// A fresh per-file git status snapshot for the root.
+    GitStatus(std::collections::HashMap<PathBuf, git::GitStatus>),
+    Quit,
+}
+
 /// Main application state and run loop.
 pub struct App {
     pub root_path: PathBuf,
@@ -29,9 +71,27 @@ pub struct App {
     pub auto_open_viewer: bool,
     pub max_depth: Option<usize>,
     pub max_files: Option<usize>,
+    /// Byte size below which `--aggr` collapses a directory's files into a
+    /// single "N small files" row (see `renderer::build_tree`). `None`
+    /// disables aggregation.
+    pub aggregate_threshold: Option<u64>,
+    /// Color the tree's indentation guides by nesting depth (see
+    /// `Theme::rainbow_palette_colors`) instead of uniform dark gray.
+    pub rainbow_edges: bool,
+    /// Metric tree siblings are ordered by (see `renderer::SortKind`).
+    pub sort: renderer::SortKind,
+    /// Reverse the `sort` ordering.
+    pub sort_reverse: bool,
     pub refresh_interval: Duration,
     pub scroll_offset: u16,
     pub total_tree_lines: u16,
+    /// Index of the currently focused tree row, within content lines (i.e.
+    /// excluding the column header). `j`/`k` move this instead of the raw
+    /// scroll offset; `render_ui` auto-scrolls to keep it in view.
+    pub selected_index: usize,
+    /// Directories currently collapsed (children hidden), toggled with
+    /// Enter on the selected row. See `renderer::render_tree_lines`.
+    pub collapsed: std::collections::HashSet<PathBuf>,
     /// Whether the search input bar is actively accepting keystrokes.
     pub search_active: bool,
     /// The current search/filter query string.
@@ -40,15 +100,59 @@ pub struct App {
     pub last_error: Option<String>,
     /// Worktree paths discovered at startup (empty if disabled).
     pub worktree_paths: Vec<PathBuf>,
+    /// Live broadcast server, running when `--serve <addr>` or `--live` was
+    /// passed.
+    pub live: Option<Arc<LiveServer>>,
+    /// Whether to open the live viewer in the browser as soon as the
+    /// broadcast server comes up, rather than waiting for the session to
+    /// end. Only set by `--live`; `--serve` alone assumes an external
+    /// viewer will connect on its own.
+    live_auto_open: bool,
+    /// Whether this session is continuing an existing recording via
+    /// `--resume` rather than starting a fresh one.
+    is_resuming: bool,
+    /// Ring buffer of recent diagnostics (`tracing` events from the watcher,
+    /// scanner, worktree discovery, and recording subsystems), shown by
+    /// toggling the log panel with `l`.
+    logs: LogBuffer,
+    /// Whether the log panel is currently visible.
+    show_logs: bool,
+    /// Whether the tree shows the opt-in `--long` Modified/Churn columns,
+    /// toggled with `D`. See `renderer::render_tree_lines`.
+    show_details: bool,
+    /// Whether to generate an LLM narrative summary at exit (`--summarize`).
+    summarize: bool,
+    /// Format to print the session summary in at exit (`--stats-format`),
+    /// in addition to the plain-text summary.
+    stats_format: Option<crate::cli::StatsFormatArg>,
+    /// Whether the bookmark-naming input bar is actively accepting
+    /// keystrokes, entered with `b` and committed with Enter.
+    bookmark_active: bool,
+    /// The bookmark name typed so far.
+    bookmark_name: String,
+    /// Semantic color roles for the tree, legend, stats dashboard, and
+    /// chrome, loaded from `--theme` or the built-in cyan scheme.
+    theme: crate::theme::Theme,
+    /// Byte size display style for the tree's Size/Delta columns and the
+    /// stats dashboard (`--compact-sizes`/`--decimal-sizes`).
+    size_format: crate::state::SizeFormat,
 }
 
 impl App {
-    /// Build a new `App` from parsed CLI arguments.
-    pub fn new(cli: &Cli) -> Result<Self> {
+    /// Build a new `App` from parsed CLI arguments. `logs` is the
+    /// diagnostics ring buffer created by `diagnostics::init()` at startup.
+    pub fn new(cli: &Cli, logs: LogBuffer) -> Result<Self> {
         let root_path = cli.path.canonicalize().unwrap_or_else(|_| cli.path.clone());
 
         // --- Event logger (recording — on by default) ---
-        let event_logger = if !cli.no_record {
+        let is_resuming = cli.resume.is_some();
+        let event_logger = if let Some(ref resume_file) = cli.resume {
+            Some(EventLogger::resume_from_file(
+                Path::new(resume_file),
+                Some(root_path.clone()),
+                cli.content,
+            )?)
+        } else if !cli.no_record {
             let recordings_dir = root_path.join("recordings");
             std::fs::create_dir_all(&recordings_dir)?;
 
@@ -82,13 +186,22 @@ impl App {
             None
         };
 
+        // --- Session log (opt-in via --stats-log) ---
+        let session_log = match cli.stats_log {
+            Some(ref path) => Some(crate::statistics::SessionLog::create(Path::new(path))?),
+            None => None,
+        };
+
         // --- Change tracker ---
         let mut tracker = ChangeTracker::new(
             root_path.clone(),
             !cli.no_gitignore,
+            !cli.no_default_ignore,
+            cli.ignore_glob.clone(),
             cli.all,
             event_logger,
             stats_tracker,
+            session_log,
         );
 
         // --- Worktree discovery (on by default) ---
@@ -96,13 +209,13 @@ impl App {
             let worktrees = git::discover_worktrees(&root_path);
             if !worktrees.is_empty() {
                 let paths: Vec<PathBuf> = worktrees.iter().map(|wt| wt.path.clone()).collect();
-                eprintln!(
-                    "Watching {} worktree{}:",
+                tracing::info!(
+                    "watching {} worktree{}:",
                     worktrees.len(),
                     if worktrees.len() == 1 { "" } else { "s" }
                 );
                 for wt in &worktrees {
-                    eprintln!("  {} [{}]", wt.path.display(), wt.branch);
+                    tracing::info!("  {} [{}]", wt.path.display(), wt.branch);
                 }
                 tracker.set_worktree_paths(paths.clone());
                 paths
@@ -115,6 +228,32 @@ impl App {
 
         let refresh_interval = Duration::from_secs_f64(cli.interval);
 
+        // --- Live broadcast server (opt-in via --serve or --live) ---
+        let live = match cli.serve {
+            Some(ref addr) => {
+                let server = Arc::new(LiveServer::start(addr)?);
+                eprintln!("Live broadcast listening on ws://{addr}");
+                Some(server)
+            }
+            None if cli.live => {
+                let server = Arc::new(LiveServer::start("127.0.0.1:0")?);
+                eprintln!("Live broadcast listening on ws://{}", server.local_addr());
+                Some(server)
+            }
+            None => None,
+        };
+
+        let theme = match cli.theme {
+            Some(ref path) => match crate::theme::Theme::load(Path::new(path)) {
+                Ok(theme) => theme,
+                Err(e) => {
+                    tracing::warn!("Failed to load theme {}: {} -- using defaults", path, e);
+                    crate::theme::Theme::default()
+                }
+            },
+            None => crate::theme::Theme::default(),
+        };
+
         Ok(Self {
             root_path,
             tracker,
@@ -123,16 +262,125 @@ impl App {
             auto_open_viewer: !cli.no_open && !cli.no_record,
             max_depth: cli.max_depth,
             max_files: cli.max_files,
+            aggregate_threshold: cli.aggregate_threshold,
+            rainbow_edges: !cli.no_rainbow_edges,
+            sort: cli.sort.into(),
+            sort_reverse: cli.sort_reverse,
             refresh_interval,
             scroll_offset: 0,
             total_tree_lines: 0,
+            selected_index: 0,
+            collapsed: std::collections::HashSet::new(),
             search_active: false,
             search_query: String::new(),
             last_error: None,
             worktree_paths,
+            live,
+            live_auto_open: cli.live && !cli.no_open,
+            is_resuming,
+            logs,
+            show_logs: false,
+            show_details: cli.long,
+            summarize: cli.summarize,
+            stats_format: cli.stats_format,
+            bookmark_active: false,
+            bookmark_name: String::new(),
+            theme,
+            size_format: crate::state::SizeFormat {
+                compact: cli.compact_sizes,
+                decimal: cli.decimal_sizes,
+            },
         })
     }
 
+    /// Forward the tracker's most recent change set to the live broadcast
+    /// server, if one is running: a fresh snapshot followed by one delta per
+    /// changed path.
+    fn broadcast_live_changes(&self) {
+        let Some(ref live) = self.live else {
+            return;
+        };
+
+        live.update_snapshot(&self.root_path, &self.tracker.current_state);
+
+        let to_relative = |path: &PathBuf| -> String {
+            path.strip_prefix(&self.root_path)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .into_owned()
+        };
+
+        for path in &self.tracker.changes.added {
+            let info = &self.tracker.current_state[path];
+            live.broadcast_event(crate::state::FileEvent {
+                timestamp: 0.0,
+                event_type: EventType::Created,
+                path: to_relative(path),
+                size: info.size,
+                is_dir: info.is_dir,
+                loc: info.loc,
+                content_hash: None,
+                branch: None,
+                commit: None,
+                author: None,
+                author_email: None,
+                commit_subject: None,
+                staged: None,
+                unstaged: None,
+                name: None,
+                from_path: None,
+                similarity: None,
+                staged_change: None,
+            });
+        }
+        for path in &self.tracker.changes.modified {
+            let info = &self.tracker.current_state[path];
+            live.broadcast_event(crate::state::FileEvent {
+                timestamp: 0.0,
+                event_type: EventType::Modified,
+                path: to_relative(path),
+                size: info.size,
+                is_dir: info.is_dir,
+                loc: info.loc,
+                content_hash: None,
+                branch: None,
+                commit: None,
+                author: None,
+                author_email: None,
+                commit_subject: None,
+                staged: None,
+                unstaged: None,
+                name: None,
+                from_path: None,
+                similarity: None,
+                staged_change: None,
+            });
+        }
+        for path in &self.tracker.changes.deleted {
+            let info = &self.tracker.previous_state[path];
+            live.broadcast_event(crate::state::FileEvent {
+                timestamp: 0.0,
+                event_type: EventType::Deleted,
+                path: to_relative(path),
+                size: info.size,
+                is_dir: info.is_dir,
+                loc: info.loc,
+                content_hash: None,
+                branch: None,
+                commit: None,
+                author: None,
+                author_email: None,
+                commit_subject: None,
+                staged: None,
+                unstaged: None,
+                name: None,
+                from_path: None,
+                similarity: None,
+                staged_change: None,
+            });
+        }
+    }
+
     /// Compress a recording into a `#data=...` URL fragment.
     fn compress_recording(&self, recording_path: &std::path::Path) -> Result<String> {
         use base64::engine::general_purpose::URL_SAFE_NO_PAD;
@@ -143,19 +391,23 @@ impl App {
 
         let raw = std::fs::read_to_string(recording_path)?;
 
-        // Parse and strip file contents to keep URL compact.
+        // Parse and strip file contents (and the blob store they reference)
+        // to keep URL compact.
         let mut data: serde_json::Value = serde_json::from_str(&raw)?;
+        if let Some(obj) = data.as_object_mut() {
+            obj.remove("blobs");
+        }
         if let Some(initial) = data.get_mut("initial_state").and_then(|v| v.as_array_mut()) {
             for item in initial.iter_mut() {
                 if let Some(obj) = item.as_object_mut() {
-                    obj.remove("content");
+                    obj.remove("content_hash");
                 }
             }
         }
         if let Some(events) = data.get_mut("events").and_then(|v| v.as_array_mut()) {
             for event in events.iter_mut() {
                 if let Some(obj) = event.as_object_mut() {
-                    obj.remove("content");
+                    obj.remove("content_hash");
                 }
             }
         }
@@ -168,29 +420,344 @@ impl App {
         Ok(URL_SAFE_NO_PAD.encode(&compressed))
     }
 
-    /// Open the recording in the web viewer via a local HTTP server.
+    /// Open the recording in the web viewer via a local HTTP server. Small
+    /// recordings are inlined into the URL as a compressed `#data=...`
+    /// fragment; recordings past `LARGE_RECORDING_BYTES` are instead served
+    /// from disk at `/recording.json` with range support, so the viewer can
+    /// seek into them without loading the whole session up front.
     fn open_viewer(&self, recording_path: &std::path::Path) -> Result<()> {
+        let size = std::fs::metadata(recording_path).map(|m| m.len()).unwrap_or(0);
+        if size > LARGE_RECORDING_BYTES {
+            return server::serve_recording_and_open(recording_path.to_path_buf(), Some("recording"));
+        }
+
         let encoded = self.compress_recording(recording_path)?;
         let fragment = format!("data={}", encoded);
         server::serve_and_open(Some(&fragment))
     }
 
+    /// Open the viewer pointed at the running live broadcast server instead
+    /// of a finished recording, so it starts mutating as soon as the first
+    /// events come in. The embedded viewer server exposes the session's
+    /// events over its own `/events` SSE endpoint (see `server.rs`), so the
+    /// page can animate the timeline live instead of loading a fixed dump.
+    /// `serve_live_and_open` runs until the process is interrupted, so it's
+    /// kicked off on its own thread rather than blocking the run loop that's
+    /// about to start.
+    fn open_live_viewer(&self) -> Result<()> {
+        let Some(ref live) = self.live else {
+            return Ok(());
+        };
+        let live = live.clone();
+        thread::spawn(move || {
+            if let Err(e) = server::serve_live_and_open(live, Some("live")) {
+                eprintln!("Failed to open live viewer: {}", e);
+            }
+        });
+        Ok(())
+    }
+
+    /// Build the tree exactly as `renderer::render_ui` would (same sort,
+    /// filter, and aggregation settings) and resolve the path of the
+    /// currently selected row, if any. `None` for an empty tree or a
+    /// depth/file-count truncation placeholder.
+    fn selected_path(&self) -> Option<PathBuf> {
+        let tree_nodes = renderer::build_tree(
+            &self.root_path,
+            &self.tracker.current_state,
+            self.aggregate_threshold,
+            self.sort,
+            self.sort_reverse,
+            &self.tracker.changes,
+        );
+        let tree_nodes = if self.search_query.is_empty() {
+            tree_nodes
+        } else {
+            renderer::filter_tree(&tree_nodes, &self.search_query)
+        };
+        renderer::visible_line_paths(
+            &tree_nodes,
+            self.max_depth,
+            self.max_files,
+            self.selected_index,
+            self.selected_index + 1,
+            &self.collapsed,
+        )
+        .into_iter()
+        .next()
+        .flatten()
+    }
+
+    /// Toggle collapse on the directory at the current selection. A no-op if
+    /// the selected row isn't a directory (e.g. a file, or a truncation
+    /// placeholder).
+    fn toggle_collapse_selected(&mut self) {
+        let Some(path) = self.selected_path() else {
+            return;
+        };
+        if !matches!(self.tracker.current_state.get(&path), Some(info) if info.is_dir) {
+            return;
+        }
+        if !self.collapsed.remove(&path) {
+            self.collapsed.insert(path);
+        }
+    }
+
+    /// Move `selected_index` by `delta` rows (negative moves up), clamped to
+    /// the current content line count, then scroll to keep it in view.
+    fn move_selection(&mut self, delta: i64, viewport_height: u16) {
+        let max_row = (self.total_tree_lines.saturating_sub(1) as i64 - 1).max(0);
+        let new_index = (self.selected_index as i64).saturating_add(delta).clamp(0, max_row);
+        self.selected_index = new_index as usize;
+        self.sync_scroll_to_selection(viewport_height);
+    }
+
+    /// Adjust `scroll_offset` so the selected row stays within the viewport.
+    /// Mirrors the visible-window math in `renderer::render_ui`: the column
+    /// header occupies line 0, so the selected content row sits at global
+    /// line `selected_index + 1`, and `[scroll_offset, scroll_offset +
+    /// viewport_height)` is the window `render_ui` actually draws.
+    fn sync_scroll_to_selection(&mut self, viewport_height: u16) {
+        let selected_line = self.selected_index as u16 + 1;
+        if selected_line < self.scroll_offset {
+            self.scroll_offset = selected_line;
+        } else if selected_line >= self.scroll_offset.saturating_add(viewport_height) {
+            self.scroll_offset = selected_line + 1 - viewport_height;
+        }
+        let max_scroll = self.total_tree_lines.saturating_sub(viewport_height);
+        self.scroll_offset = self.scroll_offset.min(max_scroll);
+    }
+
+    /// Handle one keyboard event. Returns `true` if the run loop should exit.
+    fn handle_key(&mut self, key: KeyEvent, term_height: u16) -> bool {
+        // Ctrl+C always quits, regardless of search state.
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return true;
+        }
+
+        // Compute scroll dimensions for scroll key handling.
+        let stats_height: u16 = if self.show_stats && self.tracker.stats_tracker.is_some() {
+            9
+        } else {
+            0
+        };
+        let overhead = 3 + 1 + stats_height + 1;
+        let viewport_height = term_height.saturating_sub(overhead);
+        let half_page = viewport_height / 2;
+
+        if self.bookmark_active {
+            // Bookmark-naming input mode: typing into the bookmark bar.
+            match key.code {
+                KeyCode::Esc => {
+                    self.bookmark_active = false;
+                    self.bookmark_name.clear();
+                }
+                KeyCode::Enter => {
+                    self.bookmark_active = false;
+                    let name = std::mem::take(&mut self.bookmark_name);
+                    if !name.is_empty() {
+                        if let Some(ref mut logger) = self.tracker.event_logger {
+                            logger.log_bookmark(name);
+                        }
+                    }
+                }
+                KeyCode::Backspace => {
+                    self.bookmark_name.pop();
+                }
+                KeyCode::Char(c) => {
+                    self.bookmark_name.push(c);
+                }
+                _ => {}
+            }
+        } else if self.search_active {
+            // Search input mode: typing into the search bar.
+            match key.code {
+                KeyCode::Esc => {
+                    self.search_active = false;
+                    self.search_query.clear();
+                }
+                KeyCode::Enter => {
+                    self.search_active = false;
+                }
+                KeyCode::Backspace => {
+                    self.search_query.pop();
+                }
+                KeyCode::Char(c) => {
+                    self.search_query.push(c);
+                }
+                _ => {}
+            }
+        } else if !self.search_query.is_empty() {
+            // Filter active but not in input mode — scroll + filter keys.
+            match key.code {
+                KeyCode::Esc => {
+                    self.search_query.clear();
+                }
+                KeyCode::Char('/') => {
+                    self.search_active = true;
+                }
+                KeyCode::Char('q') | KeyCode::Char('Q') => return true,
+                KeyCode::Char('l') => {
+                    self.show_logs = !self.show_logs;
+                }
+                KeyCode::Char('s') => {
+                    self.sort = self.sort.next();
+                }
+                KeyCode::Char('S') => {
+                    self.sort_reverse = !self.sort_reverse;
+                }
+                KeyCode::Char('D') => {
+                    self.show_details = !self.show_details;
+                }
+                KeyCode::Enter => {
+                    self.toggle_collapse_selected();
+                }
+                KeyCode::Char('j') | KeyCode::Down => {
+                    self.move_selection(1, viewport_height);
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    self.move_selection(-1, viewport_height);
+                }
+                KeyCode::PageDown => {
+                    self.move_selection(half_page as i64, viewport_height);
+                }
+                KeyCode::PageUp => {
+                    self.move_selection(-(half_page as i64), viewport_height);
+                }
+                KeyCode::Char('g') | KeyCode::Home => {
+                    self.move_selection(i64::MIN, viewport_height);
+                }
+                KeyCode::Char('G') | KeyCode::End => {
+                    self.move_selection(i64::MAX, viewport_height);
+                }
+                _ => {}
+            }
+        } else {
+            // Normal mode — scroll + quit + search activation.
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Char('Q') => return true,
+                KeyCode::Char('/') => {
+                    self.search_active = true;
+                }
+                KeyCode::Char('l') => {
+                    self.show_logs = !self.show_logs;
+                }
+                KeyCode::Char('p') if self.is_recording => {
+                    self.tracker.recording_paused = !self.tracker.recording_paused;
+                }
+                KeyCode::Char('b') if self.is_recording => {
+                    self.bookmark_active = true;
+                }
+                KeyCode::Char('s') => {
+                    self.sort = self.sort.next();
+                }
+                KeyCode::Char('S') => {
+                    self.sort_reverse = !self.sort_reverse;
+                }
+                KeyCode::Char('D') => {
+                    self.show_details = !self.show_details;
+                }
+                KeyCode::Enter => {
+                    self.toggle_collapse_selected();
+                }
+                KeyCode::Char('j') | KeyCode::Down => {
+                    self.move_selection(1, viewport_height);
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    self.move_selection(-1, viewport_height);
+                }
+                KeyCode::PageDown => {
+                    self.move_selection(half_page as i64, viewport_height);
+                }
+                KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.move_selection(half_page as i64, viewport_height);
+                }
+                KeyCode::PageUp => {
+                    self.move_selection(-(half_page as i64), viewport_height);
+                }
+                KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.move_selection(-(half_page as i64), viewport_height);
+                }
+                KeyCode::Char('g') | KeyCode::Home => {
+                    self.move_selection(i64::MIN, viewport_height);
+                }
+                KeyCode::Char('G') | KeyCode::End => {
+                    self.move_selection(i64::MAX, viewport_height);
+                }
+                _ => {}
+            }
+        }
+
+        false
+    }
+
     /// Run the main TUI event loop.
     pub fn run(&mut self) -> Result<()> {
         // 1. Initial scan — do NOT log these as events.
         //    Temporarily take the event_logger out so the first `update` call
         //    doesn't record the entire initial tree as "created" events.
         let logger_backup = self.tracker.event_logger.take();
-        self.tracker.update(&self.root_path);
 
-        // If we had a logger, capture the initial state and start recording,
-        // then put it back.
+        // When resuming, seed `current_state` with the folded state from the
+        // resumed recording so `update`'s rotation turns it into
+        // `previous_state` — the first diff then only reports files that
+        // actually changed while chronocode wasn't running, instead of the
+        // whole tree as freshly "created".
+        if self.is_resuming {
+            if let Some(ref logger) = logger_backup {
+                self.tracker.current_state = logger.fold_state(&self.root_path);
+            }
+            // A resumed session's `current_state` is pre-seeded from the
+            // recording, so the diff needs a single synchronous scan against
+            // that exact seed to see what changed while chronocode wasn't
+            // running -- streaming batches in over it would race the seed.
+            self.tracker.update(&self.root_path);
+        } else {
+            // Stream the initial scan in bounded batches instead of
+            // blocking on the whole walk: each partial `ScanResult` is
+            // merged into `current_state` as it arrives and pushed to any
+            // live viewer immediately, so a huge tree shows progress
+            // instead of going silent until the walk finishes. Only the
+            // final batch carries a complete key set, so the
+            // added/deleted/modified diff and event forwarding wait for it.
+            let scan_rx = self.tracker.spawn_background_scan();
+            for result in scan_rx {
+                let is_final = !result.partial;
+                self.tracker.apply_scan_result(result);
+                if let Some(ref live) = self.live {
+                    live.update_snapshot(&self.root_path, &self.tracker.current_state);
+                }
+                if is_final {
+                    self.tracker.diff_and_forward();
+                }
+            }
+        }
+
+        if let Some(ref live) = self.live {
+            live.update_snapshot(&self.root_path, &self.tracker.current_state);
+        }
+
+        // If we had a logger, capture the initial state and start recording
+        // (unless we're resuming, where the existing initial_state/events/
+        // start_time are kept as-is and we just keep appending), then put it
+        // back.
         if let Some(mut logger) = logger_backup {
-            logger.set_initial_state(&self.tracker.current_state);
-            logger.start_recording();
+            if !self.is_resuming {
+                logger.set_initial_state(&self.tracker.current_state);
+                logger.start_recording();
+            }
             self.tracker.event_logger = Some(logger);
         }
 
+        // If `--live` is on, open the browser now, pointed at the live
+        // broadcast server, instead of waiting for the session to end — the
+        // whole point is watching the tree mutate while you code.
+        if self.live_auto_open {
+            if let Err(e) = self.open_live_viewer() {
+                eprintln!("Failed to open live viewer: {}", e);
+            }
+        }
+
         // 2. Set up the file watcher (root + any external worktrees).
         let mut watch_paths: Vec<&std::path::Path> = vec![&self.root_path];
         for wt in &self.worktree_paths {
@@ -207,29 +774,138 @@ impl App {
         let backend = CrosstermBackend::new(out);
         let mut terminal = Terminal::new(backend)?;
 
-        // 4. Main loop.
+        // 4. Fan every input source into one bounded channel — the existing
+        //    file watcher, a blocking keyboard reader, and a periodic tick —
+        //    so the main loop can block on a single `recv()` and redraw
+        //    immediately on any event instead of busy-polling.
+        let (event_tx, event_rx) = mpsc::sync_channel::<Event>(256);
+
+        let watch_tx = event_tx.clone();
+        thread::spawn(move || {
+            while let Ok(ev) = watch_rx.recv() {
+                let forwarded = match ev {
+                    WatchEvent::Created(path)
+                    | WatchEvent::Modified(path)
+                    | WatchEvent::Removed(path) => Event::FileChanged(path),
+                    WatchEvent::Error(msg) => Event::WatchError(msg),
+                };
+                if watch_tx.send(forwarded).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let tick_tx = event_tx.clone();
+        thread::spawn(move || loop {
+            thread::sleep(TICK_INTERVAL);
+            if tick_tx.send(Event::Tick).is_err() {
+                break;
+            }
+        });
+
+        // Poll the root and every worktree for branch switches or new
+        // commits. The first read of each path just seeds the baseline
+        // silently; only a change against that baseline is emitted.
+        let git_tx = event_tx.clone();
+        let git_paths: Vec<PathBuf> = std::iter::once(self.root_path.clone())
+            .chain(self.worktree_paths.iter().cloned())
+            .collect();
+        thread::spawn(move || {
+            let mut last_state: std::collections::HashMap<PathBuf, git::GitState> =
+                std::collections::HashMap::new();
+            loop {
+                for path in &git_paths {
+                    if let Some(state) = git::read_git_state(path) {
+                        let changed = last_state
+                            .get(path)
+                            .is_some_and(|prev| prev.branch != state.branch || prev.commit != state.commit);
+                        if changed && git_tx.send(Event::GitInfo(path.clone(), state.clone())).is_err() {
+                            return;
+                        }
+                        last_state.insert(path.clone(), state);
+                    }
+                }
+                thread::sleep(GIT_POLL_INTERVAL);
+            }
+        });
+
+        // Poll per-file git status for the root on its own cadence --
+        // shelling out to `git status` walks the whole working tree, so it's
+        // run less often than the cheap branch/HEAD poll above.
+        let git_status_tx = event_tx.clone();
+        let status_root = self.root_path.clone();
+        thread::spawn(move || loop {
+            let statuses = git::read_git_status(&status_root);
+            if git_status_tx.send(Event::GitStatus(statuses)).is_err() {
+                return;
+            }
+            thread::sleep(GIT_STATUS_POLL_INTERVAL);
+        });
+
+        let key_tx = event_tx;
+        thread::spawn(move || loop {
+            match event::read() {
+                Ok(CEvent::Key(key)) => {
+                    if key_tx.send(Event::Key(key)).is_err() {
+                        break;
+                    }
+                }
+                Ok(_) => {}
+                Err(_) => {
+                    let _ = key_tx.send(Event::Quit);
+                    break;
+                }
+            }
+        });
+
+        // 5. Main loop.
         let mut last_update = Instant::now();
-        let mut pending_change = false;
+        let mut pending_paths: std::collections::HashSet<PathBuf> = std::collections::HashSet::new();
 
         loop {
-            // --- Check for filesystem changes (non-blocking) ---
-            // Drain all pending watcher events.
-            loop {
-                match watch_rx.try_recv() {
-                    Ok(WatchEvent::FileChanged) => pending_change = true,
-                    Ok(WatchEvent::Error(msg)) => {
-                        self.last_error = Some(msg);
+            match event_rx.recv() {
+                Ok(Event::FileChanged(path)) => {
+                    pending_paths.insert(path);
+                }
+                Ok(Event::WatchError(msg)) => {
+                    tracing::warn!("{msg}");
+                    self.last_error = Some(msg);
+                }
+                Ok(Event::Tick) => {}
+                Ok(Event::GitInfo(path, state)) => {
+                    if let Some(ref mut logger) = self.tracker.event_logger {
+                        logger.log_git_event(
+                            &path,
+                            state.branch,
+                            state.commit,
+                            state.staged,
+                            state.unstaged,
+                        );
+                    }
+                }
+                Ok(Event::GitStatus(statuses)) => {
+                    self.tracker.apply_git_status(statuses);
+                    self.broadcast_live_changes();
+                }
+                Ok(Event::Quit) => break,
+                Ok(Event::Key(key)) => {
+                    if self.handle_key(key, terminal.size()?.height) {
+                        break;
                     }
-                    Err(_) => break,
                 }
+                Err(_) => break,
             }
 
-            // Apply pending changes once the refresh interval has elapsed.
-            if pending_change && last_update.elapsed() >= self.refresh_interval {
-                self.tracker.update(&self.root_path);
+            // Apply pending changes once the refresh interval has elapsed,
+            // coalescing whatever burst of `FileChanged` events arrived in
+            // between. Restat just the paths that were actually touched
+            // instead of re-walking the whole tree on every tick.
+            if !pending_paths.is_empty() && last_update.elapsed() >= self.refresh_interval {
+                let paths: Vec<PathBuf> = pending_paths.drain().collect();
+                self.tracker.update_from_events(&paths);
+                self.broadcast_live_changes();
                 self.last_error = None;
                 last_update = Instant::now();
-                pending_change = false;
             }
 
             // --- Draw ---
@@ -238,6 +914,15 @@ impl App {
             let search_query = self.search_query.clone();
             let search_active = self.search_active;
             let last_error = self.last_error.clone();
+            let recording_paused = self.tracker.recording_paused;
+            let bookmark_active = self.bookmark_active;
+            let bookmark_name = self.bookmark_name.clone();
+            let logs = if self.show_logs {
+                Some(self.logs.snapshot())
+            } else {
+                None
+            };
+            let selected_path = self.selected_path();
             terminal.draw(|frame| {
                 let stats = self.tracker.stats_tracker.as_ref().map(|st| st.get_stats());
                 total_lines_out = renderer::render_ui(
@@ -255,127 +940,25 @@ impl App {
                     &search_query,
                     search_active,
                     last_error.as_deref(),
+                    logs.as_deref(),
+                    recording_paused,
+                    bookmark_active,
+                    &bookmark_name,
+                    self.aggregate_threshold,
+                    self.rainbow_edges,
+                    selected_path.as_deref(),
+                    self.sort,
+                    self.sort_reverse,
+                    &self.theme,
+                    &self.collapsed,
+                    self.size_format,
+                    self.show_details,
                 );
             })?;
             self.total_tree_lines = total_lines_out;
-
-            // --- Handle keyboard events ---
-            // Use a short poll timeout so we cycle back quickly to check
-            // for filesystem changes.
-            if event::poll(Duration::from_millis(50))? {
-                if let Event::Key(key) = event::read()? {
-                    // Ctrl+C always quits, regardless of search state.
-                    if key.code == KeyCode::Char('c')
-                        && key.modifiers.contains(KeyModifiers::CONTROL)
-                    {
-                        break;
-                    }
-
-                    // Compute scroll dimensions for scroll key handling.
-                    let term_height = terminal.size()?.height;
-                    let stats_height: u16 =
-                        if self.show_stats && self.tracker.stats_tracker.is_some() {
-                            9
-                        } else {
-                            0
-                        };
-                    let overhead = 3 + 1 + stats_height + 1;
-                    let viewport_height = term_height.saturating_sub(overhead);
-                    let max_scroll = self.total_tree_lines.saturating_sub(viewport_height);
-                    let half_page = viewport_height / 2;
-
-                    if self.search_active {
-                        // Search input mode: typing into the search bar.
-                        match key.code {
-                            KeyCode::Esc => {
-                                self.search_active = false;
-                                self.search_query.clear();
-                            }
-                            KeyCode::Enter => {
-                                self.search_active = false;
-                            }
-                            KeyCode::Backspace => {
-                                self.search_query.pop();
-                            }
-                            KeyCode::Char(c) => {
-                                self.search_query.push(c);
-                            }
-                            _ => {}
-                        }
-                    } else if !self.search_query.is_empty() {
-                        // Filter active but not in input mode — scroll + filter keys.
-                        match key.code {
-                            KeyCode::Esc => {
-                                self.search_query.clear();
-                            }
-                            KeyCode::Char('/') => {
-                                self.search_active = true;
-                            }
-                            KeyCode::Char('q') | KeyCode::Char('Q') => break,
-                            KeyCode::Char('j') | KeyCode::Down => {
-                                self.scroll_offset =
-                                    self.scroll_offset.saturating_add(1).min(max_scroll);
-                            }
-                            KeyCode::Char('k') | KeyCode::Up => {
-                                self.scroll_offset = self.scroll_offset.saturating_sub(1);
-                            }
-                            KeyCode::PageDown => {
-                                self.scroll_offset =
-                                    self.scroll_offset.saturating_add(half_page).min(max_scroll);
-                            }
-                            KeyCode::PageUp => {
-                                self.scroll_offset = self.scroll_offset.saturating_sub(half_page);
-                            }
-                            KeyCode::Char('g') | KeyCode::Home => {
-                                self.scroll_offset = 0;
-                            }
-                            KeyCode::Char('G') | KeyCode::End => {
-                                self.scroll_offset = max_scroll;
-                            }
-                            _ => {}
-                        }
-                    } else {
-                        // Normal mode — scroll + quit + search activation.
-                        match key.code {
-                            KeyCode::Char('q') | KeyCode::Char('Q') => break,
-                            KeyCode::Char('/') => {
-                                self.search_active = true;
-                            }
-                            KeyCode::Char('j') | KeyCode::Down => {
-                                self.scroll_offset =
-                                    self.scroll_offset.saturating_add(1).min(max_scroll);
-                            }
-                            KeyCode::Char('k') | KeyCode::Up => {
-                                self.scroll_offset = self.scroll_offset.saturating_sub(1);
-                            }
-                            KeyCode::PageDown => {
-                                self.scroll_offset =
-                                    self.scroll_offset.saturating_add(half_page).min(max_scroll);
-                            }
-                            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                                self.scroll_offset =
-                                    self.scroll_offset.saturating_add(half_page).min(max_scroll);
-                            }
-                            KeyCode::PageUp => {
-                                self.scroll_offset = self.scroll_offset.saturating_sub(half_page);
-                            }
-                            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                                self.scroll_offset = self.scroll_offset.saturating_sub(half_page);
-                            }
-                            KeyCode::Char('g') | KeyCode::Home => {
-                                self.scroll_offset = 0;
-                            }
-                            KeyCode::Char('G') | KeyCode::End => {
-                                self.scroll_offset = max_scroll;
-                            }
-                            _ => {}
-                        }
-                    }
-                }
-            }
         }
 
-        // 5. Cleanup — restore the terminal.
+        // 6. Cleanup — restore the terminal.
         disable_raw_mode()?;
         execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
         terminal.show_cursor()?;
@@ -394,6 +977,20 @@ impl App {
                 "  Events:  {} created, {} modified, {} deleted",
                 stats.total_created, stats.total_modified, stats.total_deleted
             );
+
+            if let Some(format) = self.stats_format {
+                let report_format = match format {
+                    crate::cli::StatsFormatArg::Json => crate::statistics::ReportFormat::Json,
+                    crate::cli::StatsFormatArg::Yaml => crate::statistics::ReportFormat::Yaml,
+                };
+                match stats.to_report(report_format) {
+                    Ok(report) => {
+                        println!();
+                        println!("{report}");
+                    }
+                    Err(e) => eprintln!("Failed to generate stats report: {}", e),
+                }
+            }
         }
 
         // Finalize recording and open viewer.
@@ -402,18 +999,25 @@ impl App {
         let recording_info = if let Some(ref mut logger) = self.tracker.event_logger {
             logger.finalize();
             let event_count = logger.events.len();
-            logger.output_path.clone().map(|p| (p, event_count))
+            let stats = logger.get_statistics();
+            let events = logger.events.clone();
+            logger
+                .output_path
+                .clone()
+                .map(|p| (p, event_count, stats, events))
         } else {
             None
         };
 
-        if let Some((output_path, event_count)) = recording_info {
+        if let Some((output_path, event_count, stats, events)) = recording_info {
             println!(
                 "Recording saved: {} ({} events)",
                 output_path.display(),
                 event_count
             );
 
+            narrative::summarize(self.summarize, &stats, &events);
+
             // Print a shareable command so users can send the recording.
             match self.compress_recording(&output_path) {
                 Ok(encoded) => {
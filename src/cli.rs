@@ -1,6 +1,36 @@
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use std::path::PathBuf;
 
+/// Output format for `--stats-format`.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum StatsFormatArg {
+    Json,
+    Yaml,
+}
+
+/// Tree sort metric for `--sort`, mapping onto `renderer::SortKind`.
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum SortArg {
+    #[default]
+    Name,
+    Size,
+    Loc,
+    Churn,
+    Recent,
+}
+
+impl From<SortArg> for crate::renderer::SortKind {
+    fn from(arg: SortArg) -> Self {
+        match arg {
+            SortArg::Name => crate::renderer::SortKind::Name,
+            SortArg::Size => crate::renderer::SortKind::Size,
+            SortArg::Loc => crate::renderer::SortKind::Loc,
+            SortArg::Churn => crate::renderer::SortKind::Churn,
+            SortArg::Recent => crate::renderer::SortKind::Recent,
+        }
+    }
+}
+
 /// Watch directory structure and file changes in real-time
 #[derive(Parser, Debug)]
 #[command(name = "chronocode")]
@@ -27,10 +57,44 @@ pub struct Cli {
     #[arg(short = 'd', long = "max-depth")]
     pub max_depth: Option<usize>,
 
+    /// Collapse files smaller than this many bytes per directory into a
+    /// single "N small files" row, `dutree --aggr` style. Omit to show
+    /// every file individually.
+    #[arg(long = "aggr")]
+    pub aggregate_threshold: Option<u64>,
+
+    /// Disable the rainbow-colored tree indentation guides (cycling
+    /// cyan/magenta/blue/green by nesting depth) for plain terminals.
+    #[arg(long = "no-rainbow-edges")]
+    pub no_rainbow_edges: bool,
+
+    /// Order tree siblings by this metric instead of alphabetically.
+    /// Directories are always grouped before files within a level; a
+    /// directory's own position is driven by the aggregated metric of its
+    /// whole subtree (e.g. its total size under `size`).
+    #[arg(long = "sort", value_enum, default_value = "name")]
+    pub sort: SortArg,
+
+    /// Reverse the `--sort` ordering.
+    #[arg(long = "sort-reverse")]
+    pub sort_reverse: bool,
+
     /// Disable gitignore filtering
     #[arg(long = "no-gitignore")]
     pub no_gitignore: bool,
 
+    /// Disable the built-in default ignore set (node_modules/, target/,
+    /// *.lock, common binary/media extensions, etc.), mirroring watchexec's
+    /// `--no-default-ignore`. Has no effect if `--no-gitignore` is also
+    /// passed, since no ignore rules of any kind are applied then.
+    #[arg(long = "no-default-ignore")]
+    pub no_default_ignore: bool,
+
+    /// Add an extra gitignore-syntax glob to the built-in default ignore set
+    /// (lowest precedence, same as the built-ins). Repeatable.
+    #[arg(long = "ignore-glob")]
+    pub ignore_glob: Vec<String>,
+
     /// Hide statistics dashboard
     #[arg(long = "no-stats")]
     pub no_stats: bool,
@@ -76,9 +140,113 @@ pub struct Cli {
     #[arg(long)]
     pub git: Option<String>,
 
+    /// When recording from `--git` history, linearize along the mainline
+    /// (`git log --first-parent`) and diff every commit -- merge or not --
+    /// against its first parent, instead of the default of keeping the
+    /// branched topology and combined-diffing merges against all parents.
+    #[arg(long = "git-first-parent")]
+    pub git_first_parent: bool,
+
+    /// When recording from `--git` history, only include paths matching
+    /// this glob pattern (e.g. `src/**`). Repeatable; a path needs to match
+    /// just one. If omitted, every path is a candidate before `--git-exclude`
+    /// is applied.
+    #[arg(long = "git-include")]
+    pub git_include: Vec<String>,
+
+    /// When recording from `--git` history, drop paths matching this glob
+    /// pattern (e.g. `vendor/**` or `*.lock`), even if they matched
+    /// `--git-include`. Repeatable.
+    #[arg(long = "git-exclude")]
+    pub git_exclude: Vec<String>,
+
+    /// When recording from `--git` history, append a closing frame for the
+    /// repository's current uncommitted work (staged and unstaged changes
+    /// against `HEAD`), timestamped just after the final commit, so the
+    /// recording flows seamlessly from history into what's in progress now.
+    #[arg(long = "git-working-tree")]
+    pub git_working_tree: bool,
+
     /// Disable watching git worktrees. By default, chronocode discovers
     /// worktrees via `git worktree list` and records changes in all of them.
     /// Worktree paths are always included even if they would be gitignored.
     #[arg(long = "no-worktrees")]
     pub no_worktrees: bool,
+
+    /// Run a live broadcast server at the given address (e.g.
+    /// `127.0.0.1:7777`) that streams file events to connected browser
+    /// viewers in real time, in addition to the normal watch/recording
+    /// behavior.
+    #[arg(long = "serve")]
+    pub serve: Option<String>,
+
+    /// Start a live broadcast on an OS-assigned local port and open the
+    /// viewer in the browser immediately, so it mutates in real time as you
+    /// code instead of only showing the finished recording once the session
+    /// ends. The post-session compressed-fragment export still happens as
+    /// usual. Shorthand for `--serve 127.0.0.1:0` plus auto-opening that
+    /// live URL; pass an explicit `--serve <addr>` instead if you want a
+    /// fixed address without the auto-open.
+    #[arg(long = "live")]
+    pub live: bool,
+
+    /// Build a recording by shelling out to an external loader command
+    /// instead of watching the filesystem. The loader is invoked as
+    /// `<cmd> <path>` and must stream newline-delimited JSON events matching
+    /// the recording schema on stdout.
+    #[arg(long = "loader")]
+    pub loader: Option<String>,
+
+    /// Resume a previous session by continuing to append to an existing
+    /// recording file instead of starting a new one. The file's
+    /// `initial_state` and `events` are kept as-is; new events are appended
+    /// after them, timestamped past the last recorded event plus however
+    /// long chronocode was stopped for. Implies recording even if
+    /// `--no-record` is also passed.
+    #[arg(long = "resume")]
+    pub resume: Option<String>,
+
+    /// Append every event to a JSONL session log at this path (one JSON
+    /// object per line, independent of the recording file), so the
+    /// session's statistics can be durably persisted and rebuilt later even
+    /// if chronocode is killed mid-session.
+    #[arg(long = "stats-log")]
+    pub stats_log: Option<String>,
+
+    /// Also print the session summary in this format, after the plain-text
+    /// summary, when the session ends, for scripting and CI dashboards.
+    #[arg(long = "stats-format", value_enum)]
+    pub stats_format: Option<StatsFormatArg>,
+
+    /// After the session ends, generate a short narrative summary of it via
+    /// an OpenAI-compatible chat-completions endpoint and print it as it
+    /// streams in. Requires an `OPENAI_API_KEY` in the environment; silently
+    /// skipped if that's missing or the request fails. The endpoint and
+    /// model default to OpenAI's but can be overridden with
+    /// `CHRONOCODE_SUMMARY_BASE_URL` / `CHRONOCODE_SUMMARY_MODEL`.
+    #[arg(long = "summarize")]
+    pub summarize: bool,
+
+    /// Load a color theme from this TOML file instead of the built-in cyan
+    /// scheme. Any semantic role the file doesn't set (see
+    /// `crate::theme::Theme`) keeps its built-in default.
+    #[arg(long = "theme")]
+    pub theme: Option<String>,
+
+    /// Render the Size/Delta columns and the stats dashboard's total size
+    /// in the condensed `1.2k`/`3.4M`/`5.6G` style (`du -h`-like) instead of
+    /// the default spelled-out `1.2 KB`/`3.4 MB` units.
+    #[arg(long = "compact-sizes")]
+    pub compact_sizes: bool,
+
+    /// Use a 1000-byte unit instead of 1024 for `--compact-sizes`. Has no
+    /// effect without it.
+    #[arg(long = "decimal-sizes")]
+    pub decimal_sizes: bool,
+
+    /// Show extra Modified/Churn columns in the tree (`ls -l`-style details
+    /// view), toggled at runtime with `D`. Collapses back to the compact
+    /// layout on its own if the terminal is too narrow to fit them.
+    #[arg(long = "long")]
+    pub long: bool,
 }
@@ -0,0 +1,102 @@
+//! Rolling in-memory diagnostics log.
+//!
+//! Watcher errors, scan failures, worktree discovery, and recording
+//! warnings used to go straight to `eprintln!`, which is invisible once the
+//! TUI takes over the terminal, or to `App::last_error`, which only ever
+//! remembers the most recent one. This module installs a `tracing`
+//! subscriber whose [`Layer`] pushes every formatted event into a shared
+//! ring buffer instead, which the `l` keybinding surfaces as a scrollable
+//! panel (see `renderer::render_log_panel`).
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+/// How many log lines to keep before the oldest start dropping off.
+const MAX_LOG_LINES: usize = 200;
+
+/// One formatted line in the diagnostics log.
+#[derive(Clone)]
+pub struct LogEntry {
+    pub time: String,
+    pub level: Level,
+    pub message: String,
+}
+
+/// Shared handle to the ring buffer. Cloning is cheap; both the tracing
+/// layer and `App` hold a clone of the same underlying buffer.
+#[derive(Clone)]
+pub struct LogBuffer(Arc<Mutex<VecDeque<LogEntry>>>);
+
+impl LogBuffer {
+    fn new() -> Self {
+        Self(Arc::new(Mutex::new(VecDeque::with_capacity(MAX_LOG_LINES))))
+    }
+
+    fn push(&self, entry: LogEntry) {
+        let mut buf = self.0.lock().expect("log buffer lock poisoned");
+        if buf.len() >= MAX_LOG_LINES {
+            buf.pop_front();
+        }
+        buf.push_back(entry);
+    }
+
+    /// Snapshot the current contents, oldest first.
+    pub fn snapshot(&self) -> Vec<LogEntry> {
+        self.0
+            .lock()
+            .expect("log buffer lock poisoned")
+            .iter()
+            .cloned()
+            .collect()
+    }
+}
+
+/// A `tracing_subscriber::Layer` that formats each event's `message` field
+/// and appends it to a [`LogBuffer`].
+struct RingBufferLayer {
+    buffer: LogBuffer,
+}
+
+impl<S: Subscriber> Layer<S> for RingBufferLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        self.buffer.push(LogEntry {
+            time: chrono::Local::now().format("%H:%M:%S").to_string(),
+            level: *event.metadata().level(),
+            message: visitor.message,
+        });
+    }
+}
+
+/// Pulls the `message` field out of an event; that's all the panel shows.
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        }
+    }
+}
+
+/// Install the ring-buffer subscriber as the global default and return the
+/// buffer it writes into. Call this once at startup, before any other
+/// `tracing` macros fire.
+pub fn init() -> LogBuffer {
+    let buffer = LogBuffer::new();
+    let layer = RingBufferLayer {
+        buffer: buffer.clone(),
+    };
+    let subscriber = tracing_subscriber::registry().with(layer);
+    let _ = tracing::subscriber::set_global_default(subscriber);
+    buffer
+}
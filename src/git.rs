@@ -5,11 +5,19 @@
 //! - Single commit: `--git abc123` (diff from parent to that commit)
 //! - Range: `--git abc123..def456` (all commits from abc123 to def456)
 //! - Range to HEAD: `--git abc123..` (all commits from abc123 to HEAD)
+//!
+//! Branched history (merge commits) is handled per [`MergeMode`]; whichever
+//! mode is picked, every commit resolved by [`generate_recording`] is
+//! guaranteed a well-defined diff base, so the resulting events' timestamps
+//! stay monotonic.
 
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Child, ChildStdout, Command, Stdio};
 
 use anyhow::{bail, Context, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use serde_json::{json, Value};
 
 use crate::state::{EventType, FileEvent};
@@ -24,6 +32,12 @@ struct TreeEntry {
 struct DiffEntry {
     status: char,
     path: String,
+    /// Origin path, set for `R`/`C` (rename/copy) entries -- `path` holds
+    /// the destination.
+    from_path: Option<String>,
+    /// Similarity score (0-100) git reported for a rename/copy, e.g. the
+    /// `87` in `R087\told\tnew`.
+    similarity: Option<u8>,
 }
 
 /// The result of generating a recording from git history.
@@ -34,8 +48,92 @@ pub struct GitRecording {
     pub commit_count: usize,
 }
 
+/// How merge commits are resolved into the event stream.
+///
+/// Plain `git diff-tree` emits nothing for a merge commit by default (it's
+/// ambiguous which parent to diff against), so without one of these modes
+/// every change a merge introduces -- including conflict-resolution edits --
+/// silently disappears from the recording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeMode {
+    /// Linearize history along the mainline: `git log --first-parent`
+    /// walks only first parents, and every commit (merge or not) is diffed
+    /// against its first parent. Side-branch commits that were already
+    /// recorded on an earlier pass through the branch aren't replayed
+    /// again, and every commit in the resolved list has a well-defined
+    /// single-parent diff base, so event timestamps stay monotonic.
+    FirstParent,
+    /// Keep the full branched topology, but diff merge commits with `git
+    /// diff-tree --cc` -- a combined diff against all parents at once --
+    /// so the merge's own conflict-resolution edits are captured instead
+    /// of being skipped.
+    Combined,
+}
+
+/// An include/exclude pathspec filter scoping a recording to a subtree or
+/// set of glob patterns, e.g. only `src/**` or everything except `vendor/`
+/// and `*.lock`. Patterns match against the repo-relative paths `ls-tree`
+/// and `diff-tree` already emit.
+pub struct PathFilter {
+    /// `None` means "every path passes the include check" -- only the
+    /// exclude set is consulted. `Some` means a path must match at least
+    /// one include pattern to survive.
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+}
+
+impl PathFilter {
+    /// Compile `include`/`exclude` glob patterns into a matcher. Either
+    /// list may be empty; an empty `include` list matches everything
+    /// (before excludes are applied).
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self> {
+        let include = if include.is_empty() {
+            None
+        } else {
+            Some(build_globset(include)?)
+        };
+        let exclude = build_globset(exclude)?;
+        Ok(Self { include, exclude })
+    }
+
+    /// A filter that passes every path through unchanged.
+    pub fn none() -> Self {
+        Self {
+            include: None,
+            exclude: GlobSet::empty(),
+        }
+    }
+
+    /// Whether `path` should be kept in the recording.
+    fn matches(&self, path: &str) -> bool {
+        if let Some(include) = &self.include {
+            if !include.is_match(path) {
+                return false;
+            }
+        }
+        !self.exclude.is_match(path)
+    }
+}
+
+/// Compile a list of glob-syntax patterns into a single [`GlobSet`].
+fn build_globset(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob =
+            Glob::new(pattern).with_context(|| format!("invalid glob pattern: {pattern}"))?;
+        builder.add(glob);
+    }
+    builder.build().context("failed to compile glob patterns")
+}
+
 /// Parse the `--git` spec and generate a recording.
-pub fn generate_recording(spec: &str, repo_path: &Path) -> Result<GitRecording> {
+pub fn generate_recording(
+    spec: &str,
+    repo_path: &Path,
+    merge_mode: MergeMode,
+    filter: &PathFilter,
+    include_working_tree: bool,
+) -> Result<GitRecording> {
     // Verify we're in a git repo.
     let output = Command::new("git")
         .args(["rev-parse", "--git-dir"])
@@ -47,7 +145,7 @@ pub fn generate_recording(spec: &str, repo_path: &Path) -> Result<GitRecording>
     }
 
     // Parse the spec into a list of commits.
-    let commits = resolve_commits(spec, repo_path)?;
+    let commits = resolve_commits(spec, repo_path, merge_mode)?;
     if commits.is_empty() {
         bail!("no commits found for spec: {}", spec);
     }
@@ -61,27 +159,39 @@ pub fn generate_recording(spec: &str, repo_path: &Path) -> Result<GitRecording>
     // Get the timestamp of the first commit as the recording start time.
     let start_time = get_commit_timestamp(&commits[0], repo_path)?;
 
+    // A single long-lived `git cat-file --batch` process serves every blob
+    // read below, so the whole recording costs O(1) git invocations for
+    // stats instead of one `git show` per file per commit.
+    let mut batch = BatchCatFile::spawn(repo_path)?;
+
     // Build initial state from the tree at the parent of the first commit
     // (or empty if the first commit is the root commit).
-    let initial_state = build_initial_state(&commits[0], repo_path)?;
+    let initial_state = build_initial_state(&mut batch, &commits[0], repo_path, filter)?;
 
     // Build events from each commit's diff.
     let mut events = Vec::new();
     for commit in &commits {
         let commit_time = get_commit_timestamp(commit, repo_path)?;
         let timestamp = commit_time - start_time;
-        let diff_entries = get_commit_diff(commit, repo_path)?;
+        let diff_entries = get_commit_diff(commit, repo_path, merge_mode)?;
+        let metadata = get_commit_metadata(commit, repo_path)?;
 
         for entry in diff_entries {
+            if !filter.matches(&entry.path) {
+                continue;
+            }
+
             let event_type = match entry.status {
                 'A' => EventType::Created,
                 'M' => EventType::Modified,
                 'D' => EventType::Deleted,
+                'R' => EventType::Renamed,
+                'C' => EventType::Copied,
                 _ => continue,
             };
 
             let (size, loc) = if entry.status != 'D' {
-                get_file_stats(&entry.path, commit, repo_path)
+                get_file_stats(&mut batch, &entry.path, commit)
             } else {
                 (0, 0)
             };
@@ -93,11 +203,32 @@ pub fn generate_recording(spec: &str, repo_path: &Path) -> Result<GitRecording>
                 size,
                 is_dir: false,
                 loc,
-                content: None,
+                content_hash: None,
+                branch: None,
+                commit: Some(metadata.short_hash.clone()),
+                author: Some(metadata.author_name.clone()),
+                author_email: Some(metadata.author_email.clone()),
+                commit_subject: Some(metadata.subject.clone()),
+                staged: None,
+                unstaged: None,
+                name: None,
+                from_path: entry.from_path,
+                similarity: entry.similarity,
+                staged_change: None,
             });
         }
     }
 
+    if include_working_tree {
+        let last_timestamp = get_commit_timestamp(commits.last().unwrap(), repo_path)? - start_time;
+        events.extend(working_tree_events(
+            &mut batch,
+            repo_path,
+            last_timestamp,
+            filter,
+        )?);
+    }
+
     let commit_count = commits.len();
     Ok(GitRecording {
         initial_state,
@@ -107,8 +238,327 @@ pub fn generate_recording(spec: &str, repo_path: &Path) -> Result<GitRecording>
     })
 }
 
+/// Synthesize closing-frame [`FileEvent`]s for the repository's current
+/// uncommitted work (staged and unstaged changes against `HEAD`), so a
+/// recording generated from history can flow seamlessly into "what I'm
+/// working on right now." Timestamped a hair after `after` to sort behind
+/// every commit event.
+fn working_tree_events(
+    batch: &mut BatchCatFile,
+    repo_path: &Path,
+    after: f64,
+    filter: &PathFilter,
+) -> Result<Vec<FileEvent>> {
+    let output = Command::new("git")
+        .args(["status", "--porcelain=v2", "-z"])
+        .current_dir(repo_path)
+        .output()
+        .context("failed to run git status")?;
+    if !output.status.success() {
+        bail!("git status failed");
+    }
+
+    let mut events = Vec::new();
+    let mut next_timestamp = after;
+    let mut push_timestamp = || {
+        next_timestamp += 0.001;
+        next_timestamp
+    };
+
+    // Same "-z" record format as `read_git_status`: a rename/copy record
+    // ("2 ...") is followed by a second NUL-terminated field holding the
+    // origin path.
+    let mut fields = output.stdout.split(|&b| b == 0).filter(|f| !f.is_empty());
+    while let Some(field) = fields.next() {
+        let line = String::from_utf8_lossy(field);
+
+        if let Some(path) = line.strip_prefix("? ") {
+            if !filter.matches(path) {
+                continue;
+            }
+            let (size, loc) = working_tree_file_stats(repo_path, path);
+            events.push(working_tree_event(
+                path.to_string(),
+                EventType::Created,
+                size,
+                loc,
+                false,
+                push_timestamp(),
+            ));
+            continue;
+        }
+        if line.starts_with('!') {
+            continue;
+        }
+
+        let is_rename = line.starts_with("2 ");
+        if !is_rename && !line.starts_with("1 ") {
+            // Unmerged ("u") conflict markers aren't surfaced as events.
+            continue;
+        }
+
+        // "1 XY sub mH mI mW hH hI path" or, for renames,
+        // "2 XY sub mH mI mW hH hI X<score> path" followed by origPath.
+        let parts: Vec<&str> = line.splitn(if is_rename { 10 } else { 9 }, ' ').collect();
+        let (Some(xy), Some(path)) = (parts.get(1), parts.last()) else {
+            continue;
+        };
+        let path = path.to_string();
+        let from_path = if is_rename { fields.next() } else { None }
+            .map(|f| String::from_utf8_lossy(f).into_owned());
+
+        if !filter.matches(&path) {
+            continue;
+        }
+
+        let mut xy_chars = xy.chars();
+        let x = xy_chars.next().unwrap_or('.');
+        let y = xy_chars.next().unwrap_or('.');
+
+        // X is the status of the index relative to HEAD; Y is the status of
+        // the worktree relative to the index. A path can appear in both.
+        if x != '.' {
+            let event_type = match x {
+                'A' => EventType::Created,
+                'D' => EventType::Deleted,
+                'R' => EventType::Renamed,
+                'C' => EventType::Copied,
+                _ => EventType::Modified,
+            };
+            let (size, loc) = if x == 'D' {
+                (0, 0)
+            } else {
+                batch.size_and_loc("", &path).unwrap_or((0, 0))
+            };
+            let mut event =
+                working_tree_event(path.clone(), event_type, size, loc, true, push_timestamp());
+            event.from_path = from_path.clone();
+            events.push(event);
+        }
+        if y != '.' {
+            let event_type = match y {
+                'D' => EventType::Deleted,
+                _ => EventType::Modified,
+            };
+            let (size, loc) = if y == 'D' {
+                (0, 0)
+            } else {
+                working_tree_file_stats(repo_path, &path)
+            };
+            events.push(working_tree_event(
+                path,
+                event_type,
+                size,
+                loc,
+                false,
+                push_timestamp(),
+            ));
+        }
+    }
+
+    Ok(events)
+}
+
+/// Read `path`'s current size and line count directly off disk, for
+/// unstaged working-tree content that isn't a git object.
+fn working_tree_file_stats(repo_path: &Path, path: &str) -> (u64, usize) {
+    let Ok(content) = std::fs::read(repo_path.join(path)) else {
+        return (0, 0);
+    };
+    let loc = content.iter().filter(|&&b| b == b'\n').count();
+    (content.len() as u64, loc)
+}
+
+fn working_tree_event(
+    path: String,
+    event_type: EventType,
+    size: u64,
+    loc: usize,
+    staged: bool,
+    timestamp: f64,
+) -> FileEvent {
+    FileEvent {
+        timestamp,
+        event_type,
+        path,
+        size,
+        is_dir: false,
+        loc,
+        content_hash: None,
+        branch: None,
+        commit: None,
+        author: None,
+        author_email: None,
+        commit_subject: None,
+        staged: None,
+        unstaged: None,
+        name: None,
+        from_path: None,
+        similarity: None,
+        staged_change: Some(staged),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Combined multi-branch / multi-worktree recordings
+// ---------------------------------------------------------------------------
+
+/// One branch or worktree to fold into a [`generate_combined_recording`]
+/// call. `label` tags every event this source produces -- typically a
+/// [`WorktreeInfo::branch`] -- so a viewer can render parallel lines of
+/// development distinctly.
+pub struct RecordingSource {
+    pub label: String,
+    pub repo_path: PathBuf,
+    pub spec: String,
+}
+
+/// Build one merged [`GitRecording`] spanning several branches or worktrees.
+///
+/// Each source's commit range is resolved independently, then every commit
+/// across all sources is interleaved into a single monotonic timeline by
+/// author timestamp. Commits reachable from more than one source (shared
+/// ancestry) are only recorded once, attributed to whichever source comes
+/// first in `sources`. Every event is tagged with its
+/// source's `label` via [`FileEvent::branch`], the same field `--git`'s
+/// single-source recordings leave unset.
+pub fn generate_combined_recording(
+    sources: &[RecordingSource],
+    merge_mode: MergeMode,
+    filter: &PathFilter,
+) -> Result<GitRecording> {
+    if sources.is_empty() {
+        bail!("no recording sources given");
+    }
+
+    // Resolve every source's commit range up front, then flatten into
+    // (timestamp, commit, source index) tuples so the whole history can be
+    // sorted into one timeline regardless of which source it came from.
+    struct Scheduled {
+        timestamp: f64,
+        commit: String,
+        source: usize,
+    }
+    let mut scheduled = Vec::new();
+    let mut seen_commits: HashMap<String, usize> = HashMap::new();
+    for (index, source) in sources.iter().enumerate() {
+        let commits = resolve_commits(&source.spec, &source.repo_path, merge_mode)?;
+        for commit in commits {
+            // A commit reachable from several branches (shared ancestry) is
+            // only ever scheduled once, against whichever source named it
+            // first.
+            seen_commits.entry(commit.clone()).or_insert(index);
+            if seen_commits[&commit] != index {
+                continue;
+            }
+            let timestamp = get_commit_timestamp(&commit, &source.repo_path)?;
+            scheduled.push(Scheduled {
+                timestamp,
+                commit,
+                source: index,
+            });
+        }
+    }
+    if scheduled.is_empty() {
+        bail!("no commits found across {} source(s)", sources.len());
+    }
+    scheduled.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+
+    let start_time = scheduled[0].timestamp;
+
+    // Each source may be a different worktree with its own object store
+    // (linked worktrees share the main repo's objects, but `cat-file` still
+    // needs to run with the right `repo_path` as its cwd), so batch
+    // processes are spawned lazily per source and kept alive for reuse.
+    let mut batches: HashMap<usize, BatchCatFile> = HashMap::new();
+
+    let initial_source = &sources[scheduled[0].source];
+    let initial_batch = batch_for_source(&mut batches, scheduled[0].source, initial_source)?;
+    let initial_state = build_initial_state(
+        initial_batch,
+        &scheduled[0].commit,
+        &initial_source.repo_path,
+        filter,
+    )?;
+
+    let mut events = Vec::new();
+    for item in &scheduled {
+        let source = &sources[item.source];
+        let batch = batch_for_source(&mut batches, item.source, source)?;
+
+        let timestamp = item.timestamp - start_time;
+        let diff_entries = get_commit_diff(&item.commit, &source.repo_path, merge_mode)?;
+        let metadata = get_commit_metadata(&item.commit, &source.repo_path)?;
+
+        for entry in diff_entries {
+            if !filter.matches(&entry.path) {
+                continue;
+            }
+
+            let event_type = match entry.status {
+                'A' => EventType::Created,
+                'M' => EventType::Modified,
+                'D' => EventType::Deleted,
+                'R' => EventType::Renamed,
+                'C' => EventType::Copied,
+                _ => continue,
+            };
+
+            let (size, loc) = if entry.status != 'D' {
+                get_file_stats(batch, &entry.path, &item.commit)
+            } else {
+                (0, 0)
+            };
+
+            events.push(FileEvent {
+                timestamp,
+                event_type,
+                path: entry.path,
+                size,
+                is_dir: false,
+                loc,
+                content_hash: None,
+                branch: Some(source.label.clone()),
+                commit: Some(metadata.short_hash.clone()),
+                author: Some(metadata.author_name.clone()),
+                author_email: Some(metadata.author_email.clone()),
+                commit_subject: Some(metadata.subject.clone()),
+                staged: None,
+                unstaged: None,
+                name: None,
+                from_path: entry.from_path,
+                similarity: entry.similarity,
+                staged_change: None,
+            });
+        }
+    }
+
+    let commit_count = scheduled.len();
+    Ok(GitRecording {
+        initial_state,
+        events,
+        start_time,
+        commit_count,
+    })
+}
+
+/// Get the batch `cat-file` process for `source`, spawning one the first
+/// time this source index is touched.
+fn batch_for_source<'a>(
+    batches: &'a mut HashMap<usize, BatchCatFile>,
+    index: usize,
+    source: &RecordingSource,
+) -> Result<&'a mut BatchCatFile> {
+    match batches.entry(index) {
+        std::collections::hash_map::Entry::Occupied(e) => Ok(e.into_mut()),
+        std::collections::hash_map::Entry::Vacant(e) => {
+            Ok(e.insert(BatchCatFile::spawn(&source.repo_path)?))
+        }
+    }
+}
+
 /// Resolve a git spec into an ordered list of commit hashes.
-fn resolve_commits(spec: &str, repo_path: &Path) -> Result<Vec<String>> {
+fn resolve_commits(spec: &str, repo_path: &Path, merge_mode: MergeMode) -> Result<Vec<String>> {
     if spec.contains("..") {
         // Range: A..B or A..
         let parts: Vec<&str> = spec.splitn(2, "..").collect();
@@ -123,14 +573,22 @@ fn resolve_commits(spec: &str, repo_path: &Path) -> Result<Vec<String>> {
         let from_hash = resolve_rev(from, repo_path)?;
         let to_hash = resolve_rev(to, repo_path)?;
 
-        // Get all commits in the range, oldest first.
+        // Get all commits in the range, oldest first. `--first-parent`
+        // linearizes along the mainline so side-branch commits already
+        // folded in by an earlier merge aren't walked (and diffed) a
+        // second time.
+        let mut args = vec![
+            "log".to_string(),
+            "--format=%H".to_string(),
+            "--reverse".to_string(),
+        ];
+        if merge_mode == MergeMode::FirstParent {
+            args.push("--first-parent".to_string());
+        }
+        args.push(format!("{}..{}", from_hash, to_hash));
+
         let output = Command::new("git")
-            .args([
-                "log",
-                "--format=%H",
-                "--reverse",
-                &format!("{}..{}", from_hash, to_hash),
-            ])
+            .args(&args)
             .current_dir(repo_path)
             .output()
             .context("failed to run git log")?;
@@ -189,9 +647,55 @@ fn get_commit_timestamp(hash: &str, repo_path: &Path) -> Result<f64> {
         .context("invalid commit timestamp")
 }
 
+/// Author and message details for a single commit, attached to every
+/// [`FileEvent`] it produces so a viewer can render temporal blame (tint by
+/// author, caption by commit subject) without re-shelling into git itself.
+struct CommitMetadata {
+    author_name: String,
+    author_email: String,
+    /// Abbreviated commit hash, e.g. the `abc1234` git itself would print.
+    short_hash: String,
+    subject: String,
+}
+
+/// Get the author name/email, abbreviated hash, and subject line of a
+/// commit via a single `git log -1`, NUL-separated so a subject containing
+/// `%x09`/tabs or other punctuation can't desync the fields.
+fn get_commit_metadata(commit: &str, repo_path: &Path) -> Result<CommitMetadata> {
+    let output = Command::new("git")
+        .args(["log", "-1", "--format=%an%x00%ae%x00%h%x00%s", commit])
+        .current_dir(repo_path)
+        .output()
+        .context("failed to get commit metadata")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("git log failed: {}", stderr.trim());
+    }
+
+    let line = String::from_utf8_lossy(&output.stdout);
+    let mut fields = line.trim_end_matches('\n').splitn(4, '\0');
+    let author_name = fields.next().unwrap_or_default().to_string();
+    let author_email = fields.next().unwrap_or_default().to_string();
+    let short_hash = fields.next().unwrap_or_default().to_string();
+    let subject = fields.next().unwrap_or_default().to_string();
+
+    Ok(CommitMetadata {
+        author_name,
+        author_email,
+        short_hash,
+        subject,
+    })
+}
+
 /// Build the initial state from the tree at the parent of `commit`.
 /// If the commit has no parent (root commit), returns an empty state.
-fn build_initial_state(commit: &str, repo_path: &Path) -> Result<Vec<Value>> {
+fn build_initial_state(
+    batch: &mut BatchCatFile,
+    commit: &str,
+    repo_path: &Path,
+    filter: &PathFilter,
+) -> Result<Vec<Value>> {
     // Check if this commit has a parent.
     let output = Command::new("git")
         .args(["rev-parse", "--verify", &format!("{}^", commit)])
@@ -206,8 +710,11 @@ fn build_initial_state(commit: &str, repo_path: &Path) -> Result<Vec<Value>> {
 
     let parent = String::from_utf8_lossy(&output.stdout).trim().to_string();
 
-    // List all files in the parent tree.
-    let entries = list_tree(&parent, repo_path)?;
+    // List all files in the parent tree that survive the pathspec filter.
+    let entries: Vec<TreeEntry> = list_tree(&parent, repo_path)?
+        .into_iter()
+        .filter(|entry| filter.matches(&entry.path))
+        .collect();
 
     // Also collect directory entries by scanning paths.
     let mut dirs = std::collections::HashSet::new();
@@ -240,7 +747,7 @@ fn build_initial_state(commit: &str, repo_path: &Path) -> Result<Vec<Value>> {
 
     // Add file entries.
     for entry in entries {
-        let loc = count_lines_at_rev(&entry.path, &parent, repo_path);
+        let loc = count_lines_at_rev(batch, &entry.path, &parent);
         state.push(json!({
             "path": entry.path,
             "size": entry.size,
@@ -290,10 +797,59 @@ fn list_tree(rev: &str, repo_path: &Path) -> Result<Vec<TreeEntry>> {
     Ok(entries)
 }
 
-/// Get the diff entries for a single commit (against its parent).
-fn get_commit_diff(commit: &str, repo_path: &Path) -> Result<Vec<DiffEntry>> {
+/// Get the diff entries for a single commit.
+///
+/// Merge commits (more than one parent) are ambiguous for a plain
+/// `diff-tree` -- it emits nothing for them by default -- so they're
+/// special-cased per `merge_mode`: diffed against the first parent alone
+/// (`MergeMode::FirstParent`) or combined across all parents at once
+/// (`MergeMode::Combined`). Every other commit is diffed against its single
+/// parent as before.
+fn get_commit_diff(
+    commit: &str,
+    repo_path: &Path,
+    merge_mode: MergeMode,
+) -> Result<Vec<DiffEntry>> {
+    let parents = get_parents(commit, repo_path)?;
+
+    if parents.len() <= 1 {
+        return diff_tree_name_status(
+            &["--no-commit-id", "-r", "-M", "-C", "--name-status", commit],
+            repo_path,
+        );
+    }
+
+    match merge_mode {
+        // Diff two trees directly: parent[0] vs commit. `-M`/`-C` still
+        // apply, so renames made to resolve the merge are still detected.
+        MergeMode::FirstParent => diff_tree_name_status(
+            &[
+                "--no-commit-id",
+                "-r",
+                "-M",
+                "-C",
+                "--name-status",
+                parents[0].as_str(),
+                commit,
+            ],
+            repo_path,
+        ),
+        // `--cc` emits one combined-diff line per changed path, with a
+        // status character per parent instead of a single one; rename
+        // detection (`-M`/`-C`) isn't supported in combined mode.
+        MergeMode::Combined => diff_tree_name_status(
+            &["--cc", "--no-commit-id", "-r", "--name-status", commit],
+            repo_path,
+        ),
+    }
+}
+
+/// Run `git diff-tree` with the given arguments and parse `--name-status`
+/// (or combined-diff `--cc --name-status`) output into [`DiffEntry`]s.
+fn diff_tree_name_status(args: &[&str], repo_path: &Path) -> Result<Vec<DiffEntry>> {
     let output = Command::new("git")
-        .args(["diff-tree", "--no-commit-id", "-r", "--name-status", commit])
+        .arg("diff-tree")
+        .args(args)
         .current_dir(repo_path)
         .output()
         .context("failed to run git diff-tree")?;
@@ -310,7 +866,9 @@ fn get_commit_diff(commit: &str, repo_path: &Path) -> Result<Vec<DiffEntry>> {
             continue;
         }
         // Format: <status>\t<path>
-        // For renames: R<score>\t<old_path>\t<new_path>
+        // For renames/copies: R<score>\t<old_path>\t<new_path> (likewise C).
+        // For a combined diff (`--cc`), <status> holds one character per
+        // parent instead of one overall status, e.g. "MM\tfile.rs".
         let parts: Vec<&str> = line.split('\t').collect();
         if parts.len() < 2 {
             continue;
@@ -319,24 +877,46 @@ fn get_commit_diff(commit: &str, repo_path: &Path) -> Result<Vec<DiffEntry>> {
         let status = status_str.chars().next().unwrap_or('?');
 
         match status {
+            _ if status_str.len() > 1 && status_str.chars().all(|c| "AMD".contains(c)) => {
+                // Combined-diff status (one char per parent): collapse to a
+                // single status, favoring "still changed" over the exact
+                // per-parent story -- added in every parent's absence is an
+                // add, missing from the result is a delete, anything else
+                // (including a parent disagreeing) is a modification. This
+                // has to run before the single-char 'A'|'M'|'D' arm below,
+                // since `status` is only the first character and would
+                // otherwise swallow every combined-diff status that starts
+                // with one of those letters (e.g. "AM").
+                let collapsed = if status_str.chars().all(|c| c == 'A') {
+                    'A'
+                } else if status_str.chars().all(|c| c == 'D') {
+                    'D'
+                } else {
+                    'M'
+                };
+                entries.push(DiffEntry {
+                    status: collapsed,
+                    path: parts[1].to_string(),
+                    from_path: None,
+                    similarity: None,
+                });
+            }
             'A' | 'M' | 'D' => {
                 entries.push(DiffEntry {
                     status,
                     path: parts[1].to_string(),
+                    from_path: None,
+                    similarity: None,
                 });
             }
-            'R' | 'C' => {
-                // Rename/Copy: treat as delete old + create new.
-                if parts.len() >= 3 {
-                    entries.push(DiffEntry {
-                        status: 'D',
-                        path: parts[1].to_string(),
-                    });
-                    entries.push(DiffEntry {
-                        status: 'A',
-                        path: parts[2].to_string(),
-                    });
-                }
+            'R' | 'C' if parts.len() >= 3 => {
+                let similarity = status_str[1..].parse::<u8>().ok();
+                entries.push(DiffEntry {
+                    status,
+                    path: parts[2].to_string(),
+                    from_path: Some(parts[1].to_string()),
+                    similarity,
+                });
             }
             _ => {}
         }
@@ -345,35 +925,261 @@ fn get_commit_diff(commit: &str, repo_path: &Path) -> Result<Vec<DiffEntry>> {
     Ok(entries)
 }
 
-/// Get the size and LOC of a file at a specific commit.
-fn get_file_stats(path: &str, commit: &str, repo_path: &Path) -> (u64, usize) {
-    // Get file content to compute size and LOC.
+/// Get the parent commit hashes of `commit`, in git's parent order (i.e. the
+/// mainline parent is `parents[0]`). Empty for a root commit.
+fn get_parents(commit: &str, repo_path: &Path) -> Result<Vec<String>> {
     let output = Command::new("git")
-        .args(["show", &format!("{}:{}", commit, path)])
+        .args(["rev-list", "--parents", "-n", "1", commit])
         .current_dir(repo_path)
-        .output();
+        .output()
+        .context("failed to run git rev-list")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("git rev-list failed: {}", stderr.trim());
+    }
 
-    match output {
-        Ok(out) if out.status.success() => {
-            let size = out.stdout.len() as u64;
-            let loc = out.stdout.iter().filter(|&&b| b == b'\n').count();
-            (size, loc)
+    // Format: "<commit> <parent1> <parent2> ..."
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let hashes: Vec<String> = stdout.trim().split_whitespace().map(String::from).collect();
+    Ok(hashes.into_iter().skip(1).collect())
+}
+
+/// A long-lived `git cat-file --batch` process used to read blob size and
+/// line count for many `<rev>:<path>` lookups without spawning a `git show`
+/// per file. One instance is shared across a whole [`generate_recording`]
+/// call, so the recording needs a single git invocation for blob reads
+/// rather than one per file per commit.
+///
+/// Every lookup here needs the line count, which means the blob's contents
+/// have to cross the pipe regardless -- so this always drives `--batch`
+/// rather than `--batch-check`; a size-only caller could add a second
+/// `--batch-check`-backed handle the same way if one ever needs just the
+/// size without paying for content transfer.
+struct BatchCatFile {
+    child: Child,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl BatchCatFile {
+    fn spawn(repo_path: &Path) -> Result<Self> {
+        let mut child = Command::new("git")
+            .args(["cat-file", "--batch"])
+            .current_dir(repo_path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .context("failed to spawn git cat-file --batch")?;
+        let stdout = BufReader::new(child.stdout.take().expect("stdout was piped"));
+        Ok(Self { child, stdout })
+    }
+
+    /// Look up `<rev>:<path>` and return its size and newline count, or
+    /// `None` if the object doesn't exist at that revision (or the batch
+    /// process has gone away).
+    fn size_and_loc(&mut self, rev: &str, path: &str) -> Option<(u64, usize)> {
+        let stdin = self.child.stdin.as_mut()?;
+        writeln!(stdin, "{}:{}", rev, path).ok()?;
+        stdin.flush().ok()?;
+
+        let mut header = String::new();
+        self.stdout.read_line(&mut header).ok()?;
+        let header = header.trim_end();
+
+        // A missing object reports back as "<input> missing" instead of
+        // the usual "<oid> <type> <size>" header.
+        if header.ends_with("missing") {
+            return None;
         }
-        _ => (0, 0),
+
+        let mut fields = header.split(' ');
+        let _oid = fields.next()?;
+        let _obj_type = fields.next()?;
+        let size: u64 = fields.next()?.parse().ok()?;
+
+        let mut content = vec![0u8; size as usize];
+        self.stdout.read_exact(&mut content).ok()?;
+        // cat-file appends one more newline after the content block.
+        let mut trailing_newline = [0u8; 1];
+        self.stdout.read_exact(&mut trailing_newline).ok()?;
+
+        let loc = content.iter().filter(|&&b| b == b'\n').count();
+        Some((size, loc))
     }
 }
 
+impl Drop for BatchCatFile {
+    fn drop(&mut self) {
+        // Closing stdin makes `git cat-file --batch` exit on its own.
+        drop(self.child.stdin.take());
+        let _ = self.child.wait();
+    }
+}
+
+/// Get the size and LOC of a file at a specific commit.
+fn get_file_stats(batch: &mut BatchCatFile, path: &str, commit: &str) -> (u64, usize) {
+    batch.size_and_loc(commit, path).unwrap_or((0, 0))
+}
+
 /// Count the number of lines in a file at a specific revision.
-fn count_lines_at_rev(path: &str, rev: &str, repo_path: &Path) -> usize {
-    let output = Command::new("git")
-        .args(["show", &format!("{}:{}", rev, path)])
+fn count_lines_at_rev(batch: &mut BatchCatFile, path: &str, rev: &str) -> usize {
+    batch.size_and_loc(rev, path).map_or(0, |(_, loc)| loc)
+}
+
+// ---------------------------------------------------------------------------
+// Live git-state polling
+// ---------------------------------------------------------------------------
+
+/// Snapshot of a repository's branch/commit/working-tree state, used by the
+/// live git-state input source to detect branch switches and new commits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitState {
+    pub branch: String,
+    pub commit: String,
+    pub staged: usize,
+    pub unstaged: usize,
+}
+
+/// Read the current branch, HEAD commit hash, and staged/unstaged file
+/// counts for the repository (or worktree) at `repo_path`. Returns `None`
+/// if `repo_path` isn't inside a git repository or any of the git
+/// invocations fail.
+pub fn read_git_state(repo_path: &Path) -> Option<GitState> {
+    let branch_output = Command::new("git")
+        .args(["rev-parse", "--abbrev-ref", "HEAD"])
+        .current_dir(repo_path)
+        .output()
+        .ok()?;
+    if !branch_output.status.success() {
+        return None;
+    }
+    let branch = String::from_utf8_lossy(&branch_output.stdout)
+        .trim()
+        .to_string();
+
+    let commit_output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(repo_path)
+        .output()
+        .ok()?;
+    if !commit_output.status.success() {
+        return None;
+    }
+    let commit = String::from_utf8_lossy(&commit_output.stdout)
+        .trim()
+        .to_string();
+
+    let status_output = Command::new("git")
+        .args(["status", "--porcelain=v1"])
         .current_dir(repo_path)
-        .output();
+        .output()
+        .ok()?;
+    if !status_output.status.success() {
+        return None;
+    }
 
-    match output {
-        Ok(out) if out.status.success() => out.stdout.iter().filter(|&&b| b == b'\n').count(),
-        _ => 0,
+    let mut staged = 0;
+    let mut unstaged = 0;
+    for line in String::from_utf8_lossy(&status_output.stdout).lines() {
+        let mut chars = line.chars();
+        let index_status = chars.next().unwrap_or(' ');
+        let worktree_status = chars.next().unwrap_or(' ');
+        if index_status != ' ' && index_status != '?' {
+            staged += 1;
+        }
+        if worktree_status != ' ' && worktree_status != '?' {
+            unstaged += 1;
+        }
     }
+
+    Some(GitState {
+        branch,
+        commit,
+        staged,
+        unstaged,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Per-file git status
+// ---------------------------------------------------------------------------
+
+/// A path's working-tree git status, as reported by `git status --porcelain`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitStatus {
+    Modified,
+    Added,
+    Deleted,
+    Untracked,
+    Ignored,
+}
+
+/// Run `git status --porcelain=v2 -z` and parse it into a per-path status
+/// map, keyed by absolute path. Shells out to the `git` binary rather than
+/// linking libgit2 -- Zed found the subprocess dramatically faster than
+/// libgit2 on large repos. Returns an empty map if `repo_path` isn't inside
+/// a git repository or the command fails; callers treat that the same as
+/// "nothing is dirty".
+pub fn read_git_status(repo_path: &Path) -> HashMap<PathBuf, GitStatus> {
+    let mut statuses = HashMap::new();
+
+    let output = match Command::new("git")
+        .args(["status", "--porcelain=v2", "-z"])
+        .current_dir(repo_path)
+        .output()
+    {
+        Ok(o) if o.status.success() => o,
+        _ => return statuses,
+    };
+
+    // `-z` NUL-terminates records instead of newline-separating them, and a
+    // rename/copy record ("2 ...") is followed by a second NUL-terminated
+    // field holding the origin path -- both are handled below.
+    let mut fields = output.stdout.split(|&b| b == 0).filter(|f| !f.is_empty());
+    while let Some(field) = fields.next() {
+        let line = String::from_utf8_lossy(field);
+
+        if let Some(path) = line.strip_prefix("? ") {
+            statuses.insert(repo_path.join(path), GitStatus::Untracked);
+            continue;
+        }
+        if let Some(path) = line.strip_prefix("! ") {
+            statuses.insert(repo_path.join(path), GitStatus::Ignored);
+            continue;
+        }
+
+        let is_rename = line.starts_with("2 ");
+        if !is_rename && !line.starts_with("1 ") {
+            // Unmerged ("u") conflict markers aren't surfaced as a status.
+            continue;
+        }
+
+        // "1 XY sub mH mI mW hH hI path" or, for renames,
+        // "2 XY sub mH mI mW hH hI X<score> path" followed by origPath.
+        let parts: Vec<&str> = line.splitn(if is_rename { 10 } else { 9 }, ' ').collect();
+        let (Some(xy), Some(path)) = (parts.get(1), parts.last()) else {
+            continue;
+        };
+
+        let mut xy_chars = xy.chars();
+        let x = xy_chars.next().unwrap_or('.');
+        let y = xy_chars.next().unwrap_or('.');
+        let status = if x == 'A' || y == 'A' {
+            GitStatus::Added
+        } else if x == 'D' || y == 'D' {
+            GitStatus::Deleted
+        } else {
+            GitStatus::Modified
+        };
+        statuses.insert(repo_path.join(path), status);
+
+        if is_rename {
+            fields.next(); // Discard the origin path.
+        }
+    }
+
+    statuses
 }
 
 // ---------------------------------------------------------------------------
@@ -397,7 +1203,7 @@ pub struct WorktreeInfo {
 /// Returns worktree paths **other than** the main worktree (i.e. the one
 /// at `repo_path` or its git root).  If `repo_path` is not inside a git
 /// repository, or if `git worktree list` fails, returns an empty list and
-/// prints a warning to stderr.
+/// logs a warning to the diagnostics log.
 pub fn discover_worktrees(repo_path: &Path) -> Vec<WorktreeInfo> {
     let output = match Command::new("git")
         .args(["worktree", "list", "--porcelain"])
@@ -406,14 +1212,14 @@ pub fn discover_worktrees(repo_path: &Path) -> Vec<WorktreeInfo> {
     {
         Ok(o) => o,
         Err(e) => {
-            eprintln!("Warning: failed to run `git worktree list`: {e}");
+            tracing::warn!("failed to run `git worktree list`: {e}");
             return Vec::new();
         }
     };
 
     if !output.status.success() {
-        eprintln!(
-            "Warning: `git worktree list` failed: {}",
+        tracing::warn!(
+            "`git worktree list` failed: {}",
             String::from_utf8_lossy(&output.stderr).trim()
         );
         return Vec::new();
@@ -506,3 +1312,118 @@ fn parse_worktree_porcelain(output: &str, repo_path: &Path) -> Vec<WorktreeInfo>
 
     worktrees
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn run(dir: &Path, args: &[&str]) -> std::process::Output {
+        Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .expect("failed to run git")
+    }
+
+    fn init_repo(dir: &Path) {
+        fs::create_dir_all(dir).unwrap();
+        run(dir, &["init", "-q", "-b", "main"]);
+        run(dir, &["config", "user.email", "test@example.com"]);
+        run(dir, &["config", "user.name", "Test"]);
+        run(dir, &["config", "commit.gpgsign", "false"]);
+    }
+
+    fn commit_all(dir: &Path, msg: &str) -> String {
+        run(dir, &["add", "-A"]);
+        let out = run(dir, &["commit", "-q", "-m", msg]);
+        assert!(out.status.success(), "commit failed: {:?}", out);
+        String::from_utf8_lossy(&run(dir, &["rev-parse", "HEAD"]).stdout)
+            .trim()
+            .to_string()
+    }
+
+    /// Build a repo with a real merge conflict: `main` and `feature` both
+    /// edit `file.txt` differently from their common ancestor, and the
+    /// merge resolves it to yet a third value. Returns `(repo_dir,
+    /// main_commit, feature_commit, merge_commit)`.
+    fn setup_conflicting_merge(name: &str) -> (PathBuf, String, String, String) {
+        let dir = std::env::temp_dir().join(format!("chronocode_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        init_repo(&dir);
+
+        fs::write(dir.join("file.txt"), "base\n").unwrap();
+        commit_all(&dir, "base");
+
+        run(&dir, &["checkout", "-q", "-b", "feature"]);
+        fs::write(dir.join("file.txt"), "feature\n").unwrap();
+        let feature_commit = commit_all(&dir, "feature change");
+
+        run(&dir, &["checkout", "-q", "main"]);
+        fs::write(dir.join("file.txt"), "main\n").unwrap();
+        let main_commit = commit_all(&dir, "main change");
+
+        // This is expected to conflict and stop mid-merge.
+        let _ = run(&dir, &["merge", "--no-edit", "feature"]);
+        fs::write(dir.join("file.txt"), "resolved\n").unwrap();
+        let merge_commit = commit_all(&dir, "merge feature");
+
+        (dir, main_commit, feature_commit, merge_commit)
+    }
+
+    #[test]
+    fn test_resolve_commits_first_parent_skips_side_branch() {
+        let (dir, main_commit, _feature_commit, merge_commit) =
+            setup_conflicting_merge("resolve_first_parent");
+
+        let spec = format!("{main_commit}..HEAD");
+        let commits = resolve_commits(&spec, &dir, MergeMode::FirstParent).unwrap();
+        assert_eq!(commits, vec![merge_commit], "--first-parent should skip the replayed feature-branch commit once it's folded in by the merge");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_resolve_commits_combined_includes_side_branch() {
+        let (dir, main_commit, feature_commit, merge_commit) =
+            setup_conflicting_merge("resolve_combined");
+
+        let spec = format!("{main_commit}..HEAD");
+        let commits = resolve_commits(&spec, &dir, MergeMode::Combined).unwrap();
+        assert_eq!(commits.len(), 2);
+        assert!(commits.contains(&feature_commit));
+        assert!(commits.contains(&merge_commit));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_get_commit_diff_first_parent_diffs_against_mainline_parent() {
+        let (dir, _main_commit, _feature_commit, merge_commit) =
+            setup_conflicting_merge("diff_first_parent");
+
+        let entries = get_commit_diff(&merge_commit, &dir, MergeMode::FirstParent).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path, "file.txt");
+        assert_eq!(entries[0].status, 'M');
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    /// The merge resolves `file.txt` to a value that differs from *both*
+    /// parents, so `--cc` reports one status character per parent (e.g.
+    /// "MM") -- `get_commit_diff`/`diff_tree_name_status` must collapse
+    /// that into a single entry rather than dropping or misclassifying it.
+    #[test]
+    fn test_get_commit_diff_combined_collapses_multi_parent_status() {
+        let (dir, _main_commit, _feature_commit, merge_commit) =
+            setup_conflicting_merge("diff_combined");
+
+        let entries = get_commit_diff(&merge_commit, &dir, MergeMode::Combined).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path, "file.txt");
+        assert_eq!(entries[0].status, 'M');
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
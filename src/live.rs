@@ -0,0 +1,350 @@
+//! Live session broadcast server.
+//!
+//! When `--serve <addr>` or `--live` is passed, chronocode runs a small
+//! WebSocket server alongside the TUI that streams file events to connected
+//! browser viewers in real time, instead of only writing a static recording.
+//! `--serve` takes a fixed address for an external viewer to connect to later;
+//! `--live` picks an ephemeral local port and opens the browser against it
+//! immediately, for demoing or pairing as you work. Any number of
+//! viewers may join or leave mid-session: on connection the server first
+//! pushes a full snapshot of the current state, then forwards subsequent
+//! events as incremental deltas keyed off a monotonically increasing
+//! sequence number so a reconnecting client can resume from where it
+//! dropped off.
+//!
+//! `--live` additionally serves its viewer page from the embedded HTTP
+//! server in `server.rs`, which exposes this same snapshot-then-deltas
+//! protocol over a `/events` Server-Sent Events endpoint (see
+//! [`LiveServer::subscribe`]) so a browser tab can ride it without speaking
+//! WebSocket at all. `--serve <addr>` still runs the WebSocket listener
+//! below directly for an external viewer to connect to on its own.
+//!
+//! The `replay.html` viewer is expected to grow a "connect to live URL" path
+//! that speaks this same snapshot-then-deltas protocol; that viewer-side
+//! wiring isn't part of this source tree (no `replay.html` asset is checked
+//! in here) and is left for the frontend change that accompanies it.
+
+use std::collections::{HashMap, VecDeque};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+use serde_json::{json, Value};
+use tungstenite::{Message, WebSocket};
+
+use crate::state::{EventType, FileEvent, FileInfo};
+
+/// Maximum number of queued deltas held per subscriber before directory-level
+/// churn is coalesced into a single resync marker.
+const MAX_QUEUE_LEN: usize = 256;
+
+/// A single frame pushed down to a connected subscriber.
+#[derive(Clone)]
+pub(crate) enum LiveMessage {
+    /// Full state snapshot, sent once right after a client connects.
+    Snapshot(Value),
+    /// An incremental event, tagged with its sequence number.
+    Delta(u64, FileEvent),
+    /// The subscriber has fallen too far behind; it should discard its
+    /// queue and re-request a fresh snapshot of this subtree (a relative
+    /// directory path, `""` meaning the whole tree).
+    Resync(String),
+}
+
+impl LiveMessage {
+    /// The path this message concerns, if it's a per-path delta.
+    fn path(&self) -> Option<&str> {
+        match self {
+            LiveMessage::Delta(_, event) => Some(&event.path),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn to_json(&self) -> Value {
+        match self {
+            LiveMessage::Snapshot(state) => json!({
+                "type": "snapshot",
+                "state": state,
+            }),
+            LiveMessage::Delta(seq, event) => json!({
+                "type": "event",
+                "seq": seq,
+                "event": event.to_json(),
+            }),
+            LiveMessage::Resync(dir) => json!({
+                "type": "resync",
+                "path": dir,
+            }),
+        }
+    }
+}
+
+/// Per-connection outbound queue. Bridges the broadcasting thread (which
+/// enqueues and coalesces) and the connection's own sender thread (which
+/// blocks waiting for new messages and flushes them to the socket).
+pub(crate) struct SubscriberQueue {
+    queue: Mutex<VecDeque<LiveMessage>>,
+    ready: Condvar,
+    /// Set once the connection has gone away so the broadcaster can drop us.
+    closed: Mutex<bool>,
+}
+
+impl SubscriberQueue {
+    fn new() -> Arc<Self> {
+        Arc::new(Self {
+            queue: Mutex::new(VecDeque::new()),
+            ready: Condvar::new(),
+            closed: Mutex::new(false),
+        })
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        *self.closed.lock().expect("closed lock poisoned")
+    }
+
+    pub(crate) fn mark_closed(&self) {
+        *self.closed.lock().expect("closed lock poisoned") = true;
+        self.ready.notify_all();
+    }
+
+    /// Enqueue a message, coalescing it against whatever's already pending
+    /// for the same path: a new `Modified`/`Created` replaces an older
+    /// pending one for that path, and a `Deleted` drops everything earlier
+    /// queued for it. If the queue grows past `MAX_QUEUE_LEN` afterwards,
+    /// collapse the queued entries under the busiest directory among the
+    /// oldest ones into a single resync marker for that subtree (see
+    /// `coalesce_overflow`).
+    fn push(&self, msg: LiveMessage) {
+        let mut queue = self.queue.lock().expect("queue lock poisoned");
+
+        if let LiveMessage::Delta(_, ref event) = msg {
+            match event.event_type {
+                EventType::Modified | EventType::Created | EventType::Renamed | EventType::Copied => {
+                    queue.retain(|existing| existing.path() != Some(event.path.as_str()));
+                }
+                EventType::Deleted => {
+                    queue.retain(|existing| existing.path() != Some(event.path.as_str()));
+                }
+                EventType::Git
+                | EventType::Bookmark
+                | EventType::StatusChanged
+                | EventType::Commit
+                | EventType::BranchChanged
+                | EventType::Checkout => {}
+            }
+        }
+
+        queue.push_back(msg);
+
+        if queue.len() > MAX_QUEUE_LEN {
+            coalesce_overflow(&mut queue);
+        }
+
+        drop(queue);
+        self.ready.notify_one();
+    }
+
+    /// Block until at least one message is available (or the queue closed),
+    /// then drain everything currently pending.
+    pub(crate) fn drain_blocking(&self) -> Vec<LiveMessage> {
+        let mut queue = self.queue.lock().expect("queue lock poisoned");
+        while queue.is_empty() && !self.is_closed() {
+            queue = self.ready.wait(queue).expect("queue lock poisoned");
+        }
+        queue.drain(..).collect()
+    }
+}
+
+/// Find the parent directory that occurs most often among the oldest
+/// (overflow * 1.5) queued deltas, drop every queued delta anywhere in that
+/// directory's subtree, and put a single `Resync` marker for it at the front
+/// of the queue (the client just re-fetches a fresh snapshot of the affected
+/// subtree). If none of those oldest entries is a delta, the oldest
+/// `overflow` entries are dropped instead.
+fn coalesce_overflow(queue: &mut VecDeque<LiveMessage>) {
+    let overflow = queue.len() - MAX_QUEUE_LEN;
+    let mut by_dir: HashMap<String, usize> = HashMap::new();
+
+    for msg in queue.iter().take(overflow + overflow / 2) {
+        if let Some(path) = msg.path() {
+            let dir = Path::new(path)
+                .parent()
+                .map(|p| p.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            *by_dir.entry(dir).or_insert(0) += 1;
+        }
+    }
+
+    let Some((hottest_dir, _)) = by_dir.into_iter().max_by_key(|(_, count)| *count) else {
+        // Nothing path-scoped to coalesce (e.g. all snapshots) — just drop
+        // the oldest entries to keep the queue bounded.
+        for _ in 0..overflow {
+            queue.pop_front();
+        }
+        return;
+    };
+
+    let prefix = if hottest_dir.is_empty() {
+        String::new()
+    } else {
+        format!("{hottest_dir}/")
+    };
+
+    queue.retain(|msg| match msg.path() {
+        Some(path) => !(path == hottest_dir || path.starts_with(&prefix)),
+        None => true,
+    });
+    queue.push_front(LiveMessage::Resync(hottest_dir));
+}
+
+/// Handle to the background live-broadcast server.
+pub struct LiveServer {
+    subscribers: Arc<Mutex<Vec<Arc<SubscriberQueue>>>>,
+    /// Latest full-state snapshot, refreshed by the app after every scan and
+    /// handed to each newly connected viewer before it starts receiving
+    /// deltas.
+    latest_snapshot: Arc<Mutex<Value>>,
+    next_seq: Arc<AtomicU64>,
+    /// The address actually bound, which may differ from the requested
+    /// `addr` when it ended in `:0` (an ephemeral port picked by the OS, as
+    /// `--live` uses).
+    local_addr: std::net::SocketAddr,
+}
+
+impl LiveServer {
+    /// Bind to `addr` and start accepting WebSocket connections on a
+    /// background thread. Returns immediately; the listener loop and each
+    /// connection handler run on their own threads.
+    pub fn start(addr: &str) -> anyhow::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let local_addr = listener.local_addr()?;
+        let subscribers: Arc<Mutex<Vec<Arc<SubscriberQueue>>>> = Arc::new(Mutex::new(Vec::new()));
+        let latest_snapshot = Arc::new(Mutex::new(Value::Array(Vec::new())));
+        let next_seq = Arc::new(AtomicU64::new(0));
+
+        let accept_subscribers = subscribers.clone();
+        let accept_snapshot = latest_snapshot.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { continue };
+                let subs = accept_subscribers.clone();
+                let snapshot = accept_snapshot.clone();
+                thread::spawn(move || {
+                    if let Err(e) = handle_connection(stream, subs, snapshot) {
+                        eprintln!("live: viewer connection ended: {e}");
+                    }
+                });
+            }
+        });
+
+        Ok(Self {
+            subscribers,
+            latest_snapshot,
+            next_seq,
+            local_addr,
+        })
+    }
+
+    /// The address actually bound. Differs from the requested `addr` passed
+    /// to [`Self::start`] when it ended in `:0`.
+    pub fn local_addr(&self) -> std::net::SocketAddr {
+        self.local_addr
+    }
+
+    /// Refresh the snapshot handed to newly connecting viewers. Call this
+    /// after every `tracker.update()`.
+    pub fn update_snapshot(&self, root: &Path, state: &HashMap<PathBuf, FileInfo>) {
+        *self.latest_snapshot.lock().expect("snapshot lock poisoned") = build_snapshot(root, state);
+    }
+
+    /// Broadcast one file event to every currently-connected subscriber,
+    /// dropping any whose connection has closed.
+    pub fn broadcast_event(&self, event: FileEvent) {
+        let seq = self.next_seq.fetch_add(1, Ordering::SeqCst);
+        let msg = LiveMessage::Delta(seq, event);
+        let mut subs = self.subscribers.lock().expect("subscriber lock poisoned");
+        subs.retain(|sub| !sub.is_closed());
+        for sub in subs.iter() {
+            sub.push(msg.clone());
+        }
+    }
+
+    /// Register a new subscriber the same way an incoming WebSocket
+    /// connection would, so a plain HTTP push endpoint (e.g. the embedded
+    /// viewer server's Server-Sent Events stream) can ride the same
+    /// snapshot-then-deltas protocol without speaking WebSocket at all.
+    /// Returns the current snapshot, to send immediately, plus the queue
+    /// that will receive every subsequent delta/resync message.
+    pub(crate) fn subscribe(&self) -> (Value, Arc<SubscriberQueue>) {
+        let queue = SubscriberQueue::new();
+        self.subscribers
+            .lock()
+            .expect("subscriber lock poisoned")
+            .push(queue.clone());
+        let snapshot = self.latest_snapshot.lock().expect("snapshot lock poisoned").clone();
+        (snapshot, queue)
+    }
+}
+
+/// Build the late-joiner snapshot from the current state map, mirroring the
+/// shape of a recording's `initial_state`.
+fn build_snapshot(root: &Path, state: &HashMap<PathBuf, FileInfo>) -> Value {
+    let entries: Vec<Value> = state
+        .values()
+        .map(|info| {
+            let rel = info
+                .path
+                .strip_prefix(root)
+                .unwrap_or(&info.path)
+                .to_string_lossy()
+                .into_owned();
+            json!({
+                "path": if rel.is_empty() { ".".to_string() } else { rel },
+                "size": info.size,
+                "is_dir": info.is_dir,
+                "loc": info.loc,
+            })
+        })
+        .collect();
+    Value::Array(entries)
+}
+
+/// Perform the WebSocket handshake, send the current snapshot, then forward
+/// every subsequent broadcast event (registering for them before the
+/// snapshot is read, so no delta broadcast in between is missed).
+fn handle_connection(
+    stream: TcpStream,
+    subscribers: Arc<Mutex<Vec<Arc<SubscriberQueue>>>>,
+    latest_snapshot: Arc<Mutex<Value>>,
+) -> anyhow::Result<()> {
+    let mut socket: WebSocket<TcpStream> = tungstenite::accept(stream)?;
+
+    let queue = SubscriberQueue::new();
+    {
+        // Register before reading the snapshot so any event broadcast in
+        // between is still queued for us, not lost.
+        subscribers.lock().expect("subscriber lock poisoned").push(queue.clone());
+    }
+
+    let snapshot = latest_snapshot.lock().expect("snapshot lock poisoned").clone();
+    let snapshot_msg = LiveMessage::Snapshot(snapshot).to_json().to_string();
+    socket.send(Message::Text(snapshot_msg))?;
+
+    loop {
+        let pending = queue.drain_blocking();
+        if pending.is_empty() && queue.is_closed() {
+            break;
+        }
+        for msg in pending {
+            let text = msg.to_json().to_string();
+            if socket.send(Message::Text(text)).is_err() {
+                queue.mark_closed();
+                return Ok(());
+            }
+        }
+    }
+
+    Ok(())
+}
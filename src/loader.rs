@@ -0,0 +1,70 @@
+//! Pluggable external loaders.
+//!
+//! `--loader <cmd>` lets chronocode build a recording from an arbitrary
+//! external source instead of watching the filesystem or reading git
+//! history. chronocode invokes `<cmd> <path>`, and the loader streams
+//! newline-delimited JSON events matching [`crate::recording::EventLogger`]'s
+//! schema (`timestamp`, `path`, `event_type`, `size`, `is_dir`) to stdout.
+//! Loaded events carry no file content: a `content` field on a line is
+//! ignored. This lets people feed build logs, CI artifact
+//! manifests, or database migration histories into the same replay/share/
+//! viewer pipeline without touching core code.
+
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+
+use crate::state::FileEvent;
+
+/// Run `cmd <path>`, parse its stdout as NDJSON `FileEvent`s, and return them
+/// in the order emitted. Malformed lines are skipped with a warning rather
+/// than aborting the whole load.
+pub fn load_events(cmd: &str, path: &Path) -> Result<Vec<FileEvent>> {
+    let mut parts = cmd.split_whitespace();
+    let program = parts.next().context("--loader command is empty")?;
+    let args: Vec<&str> = parts.collect();
+
+    let mut child = Command::new(program)
+        .args(&args)
+        .arg(path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .with_context(|| format!("failed to spawn loader `{cmd}`"))?;
+
+    let stdout = child.stdout.take().context("loader produced no stdout")?;
+    let reader = BufReader::new(stdout);
+
+    let mut events = Vec::new();
+    for (line_no, line) in reader.lines().enumerate() {
+        let line = line.context("failed to read loader output")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let value: serde_json::Value = match serde_json::from_str(&line) {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("loader: skipping malformed line {}: {e}", line_no + 1);
+                continue;
+            }
+        };
+
+        match FileEvent::from_json(&value) {
+            Some(event) => events.push(event),
+            None => eprintln!(
+                "loader: skipping line {} (doesn't match the FileEvent schema)",
+                line_no + 1
+            ),
+        }
+    }
+
+    let status = child.wait().context("loader process failed")?;
+    if !status.success() {
+        bail!("loader `{cmd}` exited with {status}");
+    }
+
+    Ok(events)
+}
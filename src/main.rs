@@ -2,17 +2,25 @@
 
 mod app;
 mod cli;
+mod diagnostics;
+mod git;
 mod gitignore;
+mod live;
+mod loader;
+mod narrative;
 mod recording;
 mod renderer;
 mod scanner;
+mod server;
 mod state;
 mod statistics;
+mod theme;
 mod watcher;
 
 use clap::Parser;
 
 fn main() -> anyhow::Result<()> {
+    let log_buffer = diagnostics::init();
     let cli = cli::Cli::parse();
 
     // Handle --share mode
@@ -39,63 +47,21 @@ fn main() -> anyhow::Result<()> {
         return Ok(());
     }
 
+    // Handle --loader mode
+    if let Some(ref loader_cmd) = cli.loader {
+        handle_loader(&cli, loader_cmd)?;
+        return Ok(());
+    }
+
     // Normal watch mode
-    let mut app = app::App::new(&cli)?;
+    let mut app = app::App::new(&cli, log_buffer)?;
     app.run()?;
 
     Ok(())
 }
 
 fn handle_viewer() -> anyhow::Result<()> {
-    use std::process::{Command, Stdio};
-    use std::time::Duration;
-
-    const REPLAY_HTML: &str = include_str!("../replay.html");
-
-    let tmp_dir = std::env::temp_dir().join("chronocode-viewer");
-    std::fs::create_dir_all(&tmp_dir)?;
-    std::fs::write(tmp_dir.join("index.html"), REPLAY_HTML)?;
-
-    let port = {
-        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
-        listener.local_addr()?.port()
-    };
-
-    let mut server = Command::new("python3")
-        .args([
-            "-m",
-            "http.server",
-            &port.to_string(),
-            "--bind",
-            "127.0.0.1",
-        ])
-        .current_dir(&tmp_dir)
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .spawn()
-        .or_else(|_| {
-            Command::new("npx")
-                .args(["serve", "-l", &port.to_string(), "-s", "."])
-                .current_dir(&tmp_dir)
-                .stdout(Stdio::null())
-                .stderr(Stdio::null())
-                .spawn()
-        })
-        .map_err(|_| anyhow::anyhow!("Could not start a local server (need python3 or npx)"))?;
-
-    std::thread::sleep(Duration::from_millis(300));
-
-    let url = format!("http://127.0.0.1:{}/", port);
-    println!("Viewer running at {}", url);
-    open::that(&url)?;
-
-    // Give the browser time to load the page and all assets,
-    // then tear down the server. Once loaded, the page is self-contained.
-    std::thread::sleep(Duration::from_secs(3));
-    let _ = server.kill();
-    let _ = std::fs::remove_dir_all(&tmp_dir);
-
-    Ok(())
+    server::serve_and_open(None)
 }
 
 fn handle_share(recording_file: &str) -> anyhow::Result<()> {
@@ -112,19 +78,23 @@ fn handle_share(recording_file: &str) -> anyhow::Result<()> {
 
     let raw = std::fs::read_to_string(path)?;
 
-    // Parse and strip file contents to reduce size.
+    // Parse and strip file contents (and the blob store they reference) to
+    // reduce size.
     let mut data: serde_json::Value = serde_json::from_str(&raw)?;
+    if let Some(obj) = data.as_object_mut() {
+        obj.remove("blobs");
+    }
     if let Some(initial) = data.get_mut("initial_state").and_then(|v| v.as_array_mut()) {
         for item in initial.iter_mut() {
             if let Some(obj) = item.as_object_mut() {
-                obj.remove("content");
+                obj.remove("content_hash");
             }
         }
     }
     if let Some(events) = data.get_mut("events").and_then(|v| v.as_array_mut()) {
         for event in events.iter_mut() {
             if let Some(obj) = event.as_object_mut() {
-                obj.remove("content");
+                obj.remove("content_hash");
             }
         }
     }
@@ -154,62 +124,182 @@ fn handle_share(recording_file: &str) -> anyhow::Result<()> {
 }
 
 fn handle_load(data: &str) -> anyhow::Result<()> {
-    use std::process::{Command, Stdio};
-    use std::time::Duration;
+    let fragment = format!("data={}", data);
+    server::serve_and_open(Some(&fragment))
+}
+
+fn handle_loader(cli: &cli::Cli, loader_cmd: &str) -> anyhow::Result<()> {
+    let root_path = cli.path.canonicalize().unwrap_or_else(|_| cli.path.clone());
 
-    const REPLAY_HTML: &str = include_str!("../replay.html");
+    println!("Running loader: {}", loader_cmd);
+    let events = loader::load_events(loader_cmd, &root_path)?;
+    println!("Loader produced {} event(s)", events.len());
 
-    // Write the embedded HTML to a temp directory.
-    let tmp_dir = std::env::temp_dir().join("chronocode-viewer");
-    std::fs::create_dir_all(&tmp_dir)?;
-    std::fs::write(tmp_dir.join("index.html"), REPLAY_HTML)?;
+    let recordings_dir = root_path.join("recordings");
+    std::fs::create_dir_all(&recordings_dir)?;
+    let ts = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let output_path = recordings_dir.join(format!("recording_{}.json", ts));
 
-    // Pick a free port.
-    let port = {
-        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
-        listener.local_addr()?.port()
-    };
+    let mut logger = recording::EventLogger::new(
+        Some(output_path.clone()),
+        Some(root_path.clone()),
+        cli.content,
+    );
+    logger.start_recording();
+    // The loader already stamps each event with its own timestamp, so these
+    // are appended directly rather than going through `log_event` (which
+    // would overwrite them with wall-clock time).
+    logger.events = events;
+    logger.finalize();
 
-    // Spawn a local server. Try python3 first, then npx serve.
-    let mut server = Command::new("python3")
-        .args([
-            "-m",
-            "http.server",
-            &port.to_string(),
-            "--bind",
-            "127.0.0.1",
-        ])
-        .current_dir(&tmp_dir)
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .spawn()
-        .or_else(|_| {
-            Command::new("npx")
-                .args(["serve", "-l", &port.to_string(), "-s", "."])
-                .current_dir(&tmp_dir)
-                .stdout(Stdio::null())
-                .stderr(Stdio::null())
-                .spawn()
-        })
-        .map_err(|_| anyhow::anyhow!("Could not start a local server (need python3 or npx)"))?;
-
-    // Give the server a moment to bind.
-    std::thread::sleep(Duration::from_millis(300));
-
-    let url = format!("http://127.0.0.1:{}/#data={}", port, data);
-
-    println!("Opening viewer at http://127.0.0.1:{} ...", port);
-    open::that(&url)?;
-
-    // Give the browser time to load the page and all assets,
-    // then tear down the server. Once loaded, the page is self-contained.
-    std::thread::sleep(Duration::from_secs(3));
-    let _ = server.kill();
-    let _ = std::fs::remove_dir_all(&tmp_dir);
+    println!("Recording saved: {}", output_path.display());
 
     Ok(())
 }
 
+/// How many events apart to snapshot full state while replaying, so a seek
+/// only has to fold forward from the nearest earlier keyframe instead of
+/// from the very beginning of a (potentially huge) recording.
+const REPLAY_KEYFRAME_INTERVAL: usize = 500;
+
+/// How far a single left/right arrow press seeks, in recording-time seconds.
+const REPLAY_SEEK_SECONDS: f64 = 5.0;
+
+/// Build the initial state map from a recording's `initial_state` field.
+fn replay_initial_state(
+    root_path: &std::path::Path,
+    initial_state: &[serde_json::Value],
+) -> std::collections::HashMap<std::path::PathBuf, state::FileInfo> {
+    let mut current_state = std::collections::HashMap::new();
+    for item in initial_state {
+        let path_str = item.get("path").and_then(|v| v.as_str()).unwrap_or(".");
+        let size = item.get("size").and_then(|v| v.as_u64()).unwrap_or(0);
+        let is_dir = item
+            .get("is_dir")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let full_path = root_path.join(path_str);
+        current_state.insert(
+            full_path.clone(),
+            state::FileInfo {
+                path: full_path,
+                size,
+                modified: 0.0,
+                is_dir,
+                loc: 0,
+                git_status: None,
+            },
+        );
+    }
+    current_state
+}
+
+/// Apply a single recorded event to a state map in place.
+fn replay_apply_event(
+    state: &mut std::collections::HashMap<std::path::PathBuf, state::FileInfo>,
+    root_path: &std::path::Path,
+    ev: &state::FileEvent,
+) {
+    let full_path = root_path.join(&ev.path);
+    match ev.event_type {
+        state::EventType::Created => {
+            state.insert(
+                full_path.clone(),
+                state::FileInfo {
+                    path: full_path,
+                    size: ev.size,
+                    modified: 0.0,
+                    is_dir: ev.is_dir,
+                    loc: 0,
+                    git_status: None,
+                },
+            );
+        }
+        state::EventType::Modified => {
+            if let Some(info) = state.get_mut(&full_path) {
+                info.size = ev.size;
+            }
+        }
+        state::EventType::Deleted => {
+            state.remove(&full_path);
+        }
+        state::EventType::Renamed => {
+            let from_path = ev.from_path.as_ref().map(|p| root_path.join(p));
+            let mut info = from_path
+                .and_then(|p| state.remove(&p))
+                .unwrap_or(state::FileInfo {
+                    path: full_path.clone(),
+                    size: ev.size,
+                    modified: 0.0,
+                    is_dir: ev.is_dir,
+                    loc: 0,
+                    git_status: None,
+                });
+            info.path = full_path.clone();
+            state.insert(full_path, info);
+        }
+        state::EventType::Copied => {
+            state.insert(
+                full_path.clone(),
+                state::FileInfo {
+                    path: full_path,
+                    size: ev.size,
+                    modified: 0.0,
+                    is_dir: ev.is_dir,
+                    loc: 0,
+                    git_status: None,
+                },
+            );
+        }
+        state::EventType::Git
+        | state::EventType::Bookmark
+        | state::EventType::StatusChanged
+        | state::EventType::Commit
+        | state::EventType::BranchChanged
+        | state::EventType::Checkout => {}
+    }
+}
+
+/// Precompute periodic full-state snapshots (every `REPLAY_KEYFRAME_INTERVAL`
+/// events) so seeking backward through a large recording doesn't require
+/// folding from event zero every time. Each entry is `(events_applied, state)`.
+fn replay_build_keyframes(
+    initial_state: &std::collections::HashMap<std::path::PathBuf, state::FileInfo>,
+    events: &[state::FileEvent],
+    root_path: &std::path::Path,
+) -> Vec<(usize, std::collections::HashMap<std::path::PathBuf, state::FileInfo>)> {
+    let mut keyframes = Vec::new();
+    let mut state = initial_state.clone();
+    for (i, ev) in events.iter().enumerate() {
+        replay_apply_event(&mut state, root_path, ev);
+        if (i + 1) % REPLAY_KEYFRAME_INTERVAL == 0 {
+            keyframes.push((i + 1, state.clone()));
+        }
+    }
+    keyframes
+}
+
+/// Deterministically rebuild the state after exactly `target` events have
+/// been applied, starting from the nearest keyframe at or before `target`
+/// rather than from the initial state.
+fn replay_state_at(
+    initial_state: &std::collections::HashMap<std::path::PathBuf, state::FileInfo>,
+    keyframes: &[(usize, std::collections::HashMap<std::path::PathBuf, state::FileInfo>)],
+    events: &[state::FileEvent],
+    root_path: &std::path::Path,
+    target: usize,
+) -> std::collections::HashMap<std::path::PathBuf, state::FileInfo> {
+    let (mut applied, mut state) = match keyframes.iter().rev().find(|(n, _)| *n <= target) {
+        Some((n, snapshot)) => (*n, snapshot.clone()),
+        None => (0, initial_state.clone()),
+    };
+    while applied < target {
+        replay_apply_event(&mut state, root_path, &events[applied]);
+        applied += 1;
+    }
+    state
+}
+
 fn handle_replay(cli: &cli::Cli, replay_file: &str) -> anyhow::Result<()> {
     use std::path::Path;
     use std::time::{Duration, Instant};
@@ -240,30 +330,18 @@ fn handle_replay(cli: &cli::Cli, replay_file: &str) -> anyhow::Result<()> {
         stats.created, stats.modified, stats.deleted
     );
     println!("  Replay speed: {}x", cli.replay_speed);
+    println!(
+        "  Controls: space pause/resume, </> arrows seek {}s, [ ] step one event, 0-9 jump from start (0) to end (9) of the timeline",
+        REPLAY_SEEK_SECONDS
+    );
     println!();
 
     // Build the initial state from the recording's initial_state field.
     let root_path = cli.path.canonicalize().unwrap_or_else(|_| cli.path.clone());
-    let mut current_state = std::collections::HashMap::new();
-    for item in &logger.initial_state {
-        let path_str = item.get("path").and_then(|v| v.as_str()).unwrap_or(".");
-        let size = item.get("size").and_then(|v| v.as_u64()).unwrap_or(0);
-        let is_dir = item
-            .get("is_dir")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false);
-        let full_path = root_path.join(path_str);
-        current_state.insert(
-            full_path.clone(),
-            state::FileInfo {
-                path: full_path,
-                size,
-                modified: 0.0,
-                is_dir,
-                loc: 0,
-            },
-        );
-    }
+    let initial_state = replay_initial_state(&root_path, &logger.initial_state);
+    let events = &logger.events;
+    let keyframes = replay_build_keyframes(&initial_state, events, &root_path);
+    let duration = stats.duration_seconds.max(0.0);
 
     // Set up terminal.
     enable_raw_mode()?;
@@ -275,9 +353,32 @@ fn handle_replay(cli: &cli::Cli, replay_file: &str) -> anyhow::Result<()> {
     let changes = state::ChangeSet::default();
     let previous_state = std::collections::HashMap::new();
     let replay_speed = cli.replay_speed;
-    let events = &logger.events;
+
+    // Replay has no live log stream, selection, or interactive theme/sort
+    // toggling of its own -- just the static view the recording's own CLI
+    // flags asked for, same as `App::new` derives them for the live view.
+    let theme = match cli.theme {
+        Some(ref path) => match crate::theme::Theme::load(Path::new(path)) {
+            Ok(theme) => theme,
+            Err(e) => {
+                tracing::warn!("Failed to load theme {}: {} -- using defaults", path, e);
+                crate::theme::Theme::default()
+            }
+        },
+        None => crate::theme::Theme::default(),
+    };
+    let collapsed = std::collections::HashSet::new();
+    let sort = renderer::SortKind::from(cli.sort);
+    let size_format = state::SizeFormat {
+        compact: cli.compact_sizes,
+        decimal: cli.decimal_sizes,
+    };
+
+    let mut current_state = initial_state.clone();
     let mut event_index = 0;
-    let replay_start = Instant::now();
+    let mut playhead = 0.0_f64;
+    let mut paused = false;
+    let mut last_tick = Instant::now();
 
     loop {
         // Draw current state.
@@ -293,6 +394,23 @@ fn handle_replay(cli: &cli::Cli, replay_file: &str) -> anyhow::Result<()> {
                 cli.max_depth,
                 cli.max_files,
                 false,
+                0,
+                "",
+                false,
+                None,
+                None,
+                paused,
+                false,
+                "",
+                cli.aggregate_threshold,
+                !cli.no_rainbow_edges,
+                None,
+                sort,
+                cli.sort_reverse,
+                &theme,
+                &collapsed,
+                size_format,
+                cli.long,
             );
         })?;
 
@@ -302,46 +420,67 @@ fn handle_replay(cli: &cli::Cli, replay_file: &str) -> anyhow::Result<()> {
                 match key.code {
                     KeyCode::Char('q') | KeyCode::Char('Q') => break,
                     KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
+                    KeyCode::Char(' ') => {
+                        paused = !paused;
+                        last_tick = Instant::now();
+                    }
+                    KeyCode::Left => {
+                        playhead = (playhead - REPLAY_SEEK_SECONDS).max(0.0);
+                        last_tick = Instant::now();
+                    }
+                    KeyCode::Right => {
+                        playhead = (playhead + REPLAY_SEEK_SECONDS).min(duration);
+                        last_tick = Instant::now();
+                    }
+                    KeyCode::Char('[') => {
+                        let target = event_index.saturating_sub(1);
+                        playhead = if target == 0 {
+                            0.0
+                        } else {
+                            events[target - 1].timestamp
+                        };
+                        last_tick = Instant::now();
+                    }
+                    KeyCode::Char(']') => {
+                        if event_index < events.len() {
+                            playhead = events[event_index].timestamp;
+                        }
+                        last_tick = Instant::now();
+                    }
+                    KeyCode::Char(c) if c.is_ascii_digit() => {
+                        let tenth = c.to_digit(10).unwrap() as f64;
+                        playhead = (duration * tenth / 9.0).min(duration);
+                        last_tick = Instant::now();
+                    }
                     _ => {}
                 }
             }
         }
 
-        // Apply events whose timestamp has been reached.
-        let elapsed = replay_start.elapsed().as_secs_f64() * replay_speed;
-        while event_index < events.len() {
-            let ev = &events[event_index];
-            if ev.timestamp > elapsed {
-                break;
-            }
-            let full_path = root_path.join(&ev.path);
-            match ev.event_type {
-                state::EventType::Created => {
-                    current_state.insert(
-                        full_path.clone(),
-                        state::FileInfo {
-                            path: full_path,
-                            size: ev.size,
-                            modified: 0.0,
-                            is_dir: ev.is_dir,
-                            loc: 0,
-                        },
-                    );
-                }
-                state::EventType::Modified => {
-                    if let Some(info) = current_state.get_mut(&full_path) {
-                        info.size = ev.size;
-                    }
-                }
-                state::EventType::Deleted => {
-                    current_state.remove(&full_path);
-                }
+        // Advance the playhead by wall-clock time when not paused.
+        if !paused {
+            playhead = (playhead + last_tick.elapsed().as_secs_f64() * replay_speed).min(duration);
+        }
+        last_tick = Instant::now();
+
+        // Resolve how many events should be applied at the current playhead
+        // and bring `current_state` in line with it. Forward moves just
+        // apply the new events on top of the current state; backward seeks
+        // fold from the nearest keyframe since deletes can't be inverted.
+        let target_index = events.partition_point(|ev| ev.timestamp <= playhead);
+        if target_index > event_index {
+            for ev in &events[event_index..target_index] {
+                replay_apply_event(&mut current_state, &root_path, ev);
             }
-            event_index += 1;
+            event_index = target_index;
+        } else if target_index < event_index {
+            current_state =
+                replay_state_at(&initial_state, &keyframes, events, &root_path, target_index);
+            event_index = target_index;
         }
 
-        // End replay once all events have been applied.
-        if event_index >= events.len() {
+        // End replay once playback reaches the end and isn't paused.
+        if !paused && event_index >= events.len() && playhead >= duration {
             // Show final frame for a moment before exiting.
             std::thread::sleep(Duration::from_secs(2));
             break;
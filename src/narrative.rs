@@ -0,0 +1,125 @@
+//! Optional LLM-generated narrative summary of a session, printed at exit.
+//!
+//! Enabled with `--summarize` plus an `OPENAI_API_KEY` in the environment;
+//! without both, [`summarize`] is a silent no-op so the normal exit path is
+//! never blocked on an external service. Speaks the OpenAI chat-completions
+//! wire format (or any compatible endpoint, via `CHRONOCODE_SUMMARY_BASE_URL`
+//! / `CHRONOCODE_SUMMARY_MODEL`), streaming the response's `data: `
+//! server-sent-event lines and printing tokens as they arrive.
+
+use std::io::{BufRead, BufReader, Write};
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+
+use crate::recording::RecordingStats;
+use crate::state::{EventType, FileEvent};
+
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1/chat/completions";
+const DEFAULT_MODEL: &str = "gpt-4o-mini";
+
+/// Cap on distinct file paths sent in the prompt, to keep the request small.
+const MAX_TOUCHED_PATHS: usize = 40;
+
+/// Print a narrative summary of the session to stdout, streaming tokens as
+/// they arrive. Returns without doing anything if `enabled` is false, logs
+/// at `debug` level and returns if no API key is configured, and logs at
+/// `warn` level if the request fails.
+pub fn summarize(enabled: bool, stats: &RecordingStats, events: &[FileEvent]) {
+    if !enabled {
+        return;
+    }
+
+    let Ok(api_key) = std::env::var("OPENAI_API_KEY") else {
+        tracing::debug!("--summarize passed but OPENAI_API_KEY is not set; skipping");
+        return;
+    };
+
+    if let Err(e) = stream_summary(&api_key, stats, events) {
+        tracing::warn!("session summary generation failed: {e}");
+    }
+}
+
+/// Build the prompt, POST it with `stream: true`, and print each token as
+/// its SSE chunk arrives.
+fn stream_summary(api_key: &str, stats: &RecordingStats, events: &[FileEvent]) -> Result<()> {
+    let base_url = std::env::var("CHRONOCODE_SUMMARY_BASE_URL")
+        .unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+    let model =
+        std::env::var("CHRONOCODE_SUMMARY_MODEL").unwrap_or_else(|_| DEFAULT_MODEL.to_string());
+
+    let body = json!({
+        "model": model,
+        "stream": true,
+        "messages": [
+            {
+                "role": "system",
+                "content": "You narrate a coding session in 2-3 punchy sentences, given its \
+                    event stats and the files touched. Be specific about where the activity \
+                    was concentrated.",
+            },
+            { "role": "user", "content": build_prompt(stats, events) },
+        ],
+    });
+
+    let response = ureq::post(&base_url)
+        .set("Authorization", &format!("Bearer {api_key}"))
+        .set("Content-Type", "application/json")
+        .send_string(&body.to_string())
+        .context("sending session summary request")?;
+
+    println!();
+    println!("Session summary (generated):");
+    print!("  ");
+    std::io::stdout().flush().ok();
+
+    let reader = BufReader::new(response.into_reader());
+    for line in reader.lines() {
+        let line = line.context("reading summary stream")?;
+        let Some(payload) = line.strip_prefix("data: ") else {
+            continue;
+        };
+        if payload == "[DONE]" {
+            break;
+        }
+
+        let Ok(chunk) = serde_json::from_str::<Value>(payload) else {
+            continue;
+        };
+        if let Some(token) = chunk["choices"][0]["delta"]["content"].as_str() {
+            print!("{token}");
+            std::io::stdout().flush().ok();
+        }
+    }
+    println!();
+    println!();
+
+    Ok(())
+}
+
+/// Compress the session into a compact prompt: the numeric stats plus a
+/// deduplicated, capped list of touched paths, rather than the full
+/// timeline.
+fn build_prompt(stats: &RecordingStats, events: &[FileEvent]) -> String {
+    let mut touched: Vec<&str> = events
+        .iter()
+        .filter(|e| !matches!(e.event_type, EventType::Git))
+        .map(|e| e.path.as_str())
+        .collect();
+    touched.sort_unstable();
+    touched.dedup();
+    let truncated = touched.len() > MAX_TOUCHED_PATHS;
+    touched.truncate(MAX_TOUCHED_PATHS);
+
+    format!(
+        "Session stats: {} events ({} created, {} modified, {} deleted) over {:.0}s.\n\
+         Files touched{}: {}",
+        stats.total_events,
+        stats.created,
+        stats.modified,
+        stats.deleted,
+        stats.duration_seconds,
+        if truncated { " (truncated)" } else { "" },
+        touched.join(", "),
+    )
+}
@@ -1,11 +1,17 @@
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::SystemTime;
 
 use anyhow::{Context, Result};
 use serde_json::{self, json, Value};
 
+use crate::gitignore::GitignoreParser;
 use crate::state::{read_file_content, EventType, FileEvent, FileInfo, MAX_CONTENT_SIZE};
 
 /// Statistics about a recording session.
@@ -17,6 +23,32 @@ pub struct RecordingStats {
     pub duration_seconds: f64,
 }
 
+/// On-disk representation an [`EventLogger`] persists to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecordingFormat {
+    /// One JSON object (`start_time` + `initial_state` + `events` + `blobs`),
+    /// re-serialized and atomically swapped in (write to `.tmp`, then
+    /// rename) after every change. Simple, and the whole file is always
+    /// valid JSON on disk, but O(n^2) I/O as the event count grows -- fine
+    /// for short recordings.
+    Atomic,
+    /// A header line (`start_time` + `initial_state`) written once, followed
+    /// by one JSON line per blob and per event, opened once and only ever
+    /// appended to. Avoids re-serializing the whole recording on every
+    /// event, at the cost of the file being NDJSON rather than a single JSON
+    /// document; [`EventLogger::finalize`] only appends a trailer line.
+    Streaming,
+}
+
+/// Hash `content` for the content-addressed blob store. Not cryptographic --
+/// just stable within a single recording -- since this only needs to catch
+/// the common case of the same file contents recurring across events.
+fn hash_content(content: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
 /// Logs file system events for later replay with continuous file writing.
 pub struct EventLogger {
     pub events: Vec<FileEvent>,
@@ -25,14 +57,52 @@ pub struct EventLogger {
     pub output_path: Option<PathBuf>,
     pub root_path: Option<PathBuf>,
     pub record_content: bool,
+    /// Content-addressed store of captured file contents (hash -> text),
+    /// written once per recording. `initial_state` items and `FileEvent`s
+    /// reference entries here by hash instead of inlining their content,
+    /// so a file saved back unchanged -- or the same boilerplate appearing
+    /// in many files -- is only ever stored once.
+    pub blobs: HashMap<String, String>,
+    /// On-disk representation to write. See [`RecordingFormat`].
+    pub format: RecordingFormat,
+    /// Open handle for [`RecordingFormat::Streaming`], created on the first
+    /// write and kept for the life of the recording so every later write is
+    /// a plain append. `None` in [`RecordingFormat::Atomic`] mode, or before
+    /// the first write.
+    writer: Option<std::io::BufWriter<fs::File>>,
+    /// Blob hashes already appended as a `"record":"blob"` line in streaming
+    /// mode, so a piece of content seen again later isn't written twice.
+    flushed_blobs: HashSet<String>,
+    /// Snapshot of the scanner's [`GitignoreParser`], used to drop ignored
+    /// paths from the recording (see [`set_gitignore`](Self::set_gitignore)).
+    /// Not known at construction time -- `ChangeTracker` builds its parser
+    /// after the logger already exists -- so this starts `None` and is
+    /// wired in afterwards, then refreshed whenever a rescan picks up new
+    /// nested `.gitignore` files.
+    gitignore: Option<Arc<GitignoreParser>>,
 }
 
 impl EventLogger {
-    /// Create a new EventLogger.
+    /// Create a new EventLogger that writes in [`RecordingFormat::Atomic`].
     pub fn new(
         output_path: Option<PathBuf>,
         root_path: Option<PathBuf>,
         record_content: bool,
+    ) -> Self {
+        Self::with_format(
+            output_path,
+            root_path,
+            record_content,
+            RecordingFormat::Atomic,
+        )
+    }
+
+    /// Create a new EventLogger with an explicit [`RecordingFormat`].
+    pub fn with_format(
+        output_path: Option<PathBuf>,
+        root_path: Option<PathBuf>,
+        record_content: bool,
+        format: RecordingFormat,
     ) -> Self {
         Self {
             events: Vec::new(),
@@ -41,9 +111,44 @@ impl EventLogger {
             output_path,
             root_path,
             record_content,
+            blobs: HashMap::new(),
+            format,
+            writer: None,
+            flushed_blobs: HashSet::new(),
+            gitignore: None,
         }
     }
 
+    /// Attach (or clear) the [`GitignoreParser`] snapshot used to filter
+    /// ignored paths out of [`log_event`](Self::log_event) and
+    /// [`set_initial_state`](Self::set_initial_state).
+    ///
+    /// `ChangeTracker` calls this once it has built its own parser, and
+    /// again after every full rescan, since a nested `.gitignore` discovered
+    /// mid-walk -- or a `.gitignore` loaded after the directory it governs
+    /// was already seen -- can change a path's ignored status. Passing a
+    /// fresh snapshot each time keeps the check current instead of baking in
+    /// a decision made before the rules were fully loaded.
+    pub fn set_gitignore(&mut self, gitignore: Option<Arc<GitignoreParser>>) {
+        self.gitignore = gitignore;
+    }
+
+    /// Returns `true` if `path` should be dropped from the recording
+    /// because the attached [`GitignoreParser`] (if any) ignores it.
+    fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        self.gitignore
+            .as_ref()
+            .is_some_and(|parser| parser.is_ignored(path, is_dir))
+    }
+
+    /// Intern `content` into the blob store, returning its hash. Storing is
+    /// idempotent: content already seen this recording is not duplicated.
+    fn store_content(&mut self, content: String) -> String {
+        let hash = hash_content(&content);
+        self.blobs.entry(hash.clone()).or_insert(content);
+        hash
+    }
+
     /// Convert an absolute path to a relative path from root.
     fn to_relative_path(&self, path: &Path) -> String {
         let Some(root) = &self.root_path else {
@@ -68,6 +173,10 @@ impl EventLogger {
         self.initial_state.clear();
 
         for (path, info) in file_infos {
+            if self.is_ignored(path, info.is_dir) {
+                continue;
+            }
+
             let mut item = json!({
                 "path": self.to_relative_path(path),
                 "size": info.size,
@@ -77,7 +186,7 @@ impl EventLogger {
             // Capture content for text files when enabled
             if self.record_content && !info.is_dir {
                 if let Some(content) = read_file_content(path, MAX_CONTENT_SIZE) {
-                    item["content"] = Value::String(content);
+                    item["content_hash"] = Value::String(self.store_content(content));
                 }
             }
 
@@ -94,9 +203,12 @@ impl EventLogger {
         self.start_time = Some(now);
         self.events.clear();
 
-        // Write initial state to file
-        if self.output_path.is_some() {
-            self.write_file();
+        if self.output_path.is_none() {
+            return;
+        }
+        match self.format {
+            RecordingFormat::Atomic => self.write_file(),
+            RecordingFormat::Streaming => self.start_streaming(),
         }
     }
 
@@ -110,23 +222,131 @@ impl EventLogger {
             "start_time": self.start_time,
             "initial_state": self.initial_state,
             "events": self.events.iter().map(|e| e.to_json()).collect::<Vec<Value>>(),
+            "blobs": self.blobs,
         });
 
         let temp_path = output_path.with_extension("tmp");
         let json_bytes = serde_json::to_string(&data).expect("failed to serialize JSON");
 
         if let Err(e) = fs::write(&temp_path, json_bytes) {
-            eprintln!("Warning: failed to write temp file: {e}");
+            tracing::warn!("failed to write temp recording file {}: {e}", temp_path.display());
             return;
         }
 
         if let Err(e) = fs::rename(&temp_path, output_path) {
-            eprintln!("Warning: failed to rename temp file: {e}");
+            tracing::warn!(
+                "failed to rename {} to {}: {e}",
+                temp_path.display(),
+                output_path.display()
+            );
         }
     }
 
-    /// Log a file system event and write to file immediately.
-    pub fn log_event(&mut self, event_type: EventType, path: &Path, size: u64, is_dir: bool) {
+    /// Open the streaming file fresh (truncating any prior content), write
+    /// the header record, and flush any blobs `set_initial_state` already
+    /// captured, since they aren't tied to a later event.
+    fn start_streaming(&mut self) {
+        self.open_writer(false);
+        self.flushed_blobs.clear();
+
+        let header = json!({
+            "record": "header",
+            "start_time": self.start_time,
+            "initial_state": self.initial_state,
+        });
+        self.write_line(&header);
+
+        let hashes: Vec<String> = self
+            .initial_state
+            .iter()
+            .filter_map(|item| item.get("content_hash").and_then(|v| v.as_str()))
+            .map(|s| s.to_string())
+            .collect();
+        for hash in hashes {
+            self.flush_blob(&hash);
+        }
+    }
+
+    /// Open `output_path` for streaming writes: truncated if `append` is
+    /// `false` (a fresh recording), or kept intact and appended to if `true`
+    /// (resuming one whose header is already on disk).
+    fn open_writer(&mut self, append: bool) {
+        let Some(output_path) = &self.output_path else {
+            return;
+        };
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(output_path);
+
+        match file {
+            Ok(f) => self.writer = Some(std::io::BufWriter::new(f)),
+            Err(e) => tracing::warn!(
+                "failed to open streaming recording file {}: {e}",
+                output_path.display()
+            ),
+        }
+    }
+
+    /// Append `value` as one NDJSON line, flushing immediately so the file
+    /// on disk is never more than one event behind.
+    fn write_line(&mut self, value: &Value) {
+        if self.writer.is_none() {
+            self.open_writer(false);
+        }
+        let Some(writer) = &mut self.writer else {
+            return;
+        };
+        if let Err(e) = writeln!(writer, "{}", value) {
+            tracing::warn!("failed to append recording line: {e}");
+            return;
+        }
+        if let Err(e) = writer.flush() {
+            tracing::warn!("failed to flush recording file: {e}");
+        }
+    }
+
+    /// Append a `"record":"blob"` line for `hash` if it hasn't been written
+    /// to this streaming file yet.
+    fn flush_blob(&mut self, hash: &str) {
+        if !self.flushed_blobs.insert(hash.to_string()) {
+            return;
+        }
+        let Some(content) = self.blobs.get(hash).cloned() else {
+            return;
+        };
+        self.write_line(&json!({"record": "blob", "hash": hash, "content": content}));
+    }
+
+    /// Persist the event just pushed onto `self.events`, in whichever
+    /// format this logger is writing: a full atomic rewrite, or a single
+    /// appended NDJSON line (plus a blob line first, if its content hasn't
+    /// been flushed yet).
+    fn persist_new_event(&mut self) {
+        if self.output_path.is_none() {
+            return;
+        }
+        match self.format {
+            RecordingFormat::Atomic => self.write_file(),
+            RecordingFormat::Streaming => {
+                let Some(event) = self.events.last() else {
+                    return;
+                };
+                let hash = event.content_hash.clone();
+                let value = event.to_json();
+                if let Some(hash) = hash {
+                    self.flush_blob(&hash);
+                }
+                self.write_line(&value);
+            }
+        }
+    }
+
+    /// Compute the timestamp (seconds since recording start) for an event
+    /// logged right now, initializing `start_time` on the very first call.
+    fn next_timestamp(&mut self) -> f64 {
         let now = SystemTime::now()
             .duration_since(SystemTime::UNIX_EPOCH)
             .expect("system time before UNIX epoch")
@@ -137,14 +357,28 @@ impl EventLogger {
             self.start_time = Some(now);
         }
 
-        let timestamp = now - start;
+        now - start
+    }
+
+    /// Log a file system event and write to file immediately.
+    ///
+    /// Short-circuits without recording anything if the attached
+    /// [`GitignoreParser`] snapshot (see [`set_gitignore`](Self::set_gitignore))
+    /// ignores `path` -- e.g. a directory whose `.gitignore` was only
+    /// discovered after the scanner had already surfaced a child of it.
+    pub fn log_event(&mut self, event_type: EventType, path: &Path, size: u64, is_dir: bool) {
+        if self.is_ignored(path, is_dir) {
+            return;
+        }
+
+        let timestamp = self.next_timestamp();
 
         // Capture content for created/modified text files when enabled
-        let content = if self.record_content
+        let content_hash = if self.record_content
             && matches!(event_type, EventType::Created | EventType::Modified)
             && !is_dir
         {
-            read_file_content(path, MAX_CONTENT_SIZE)
+            read_file_content(path, MAX_CONTENT_SIZE).map(|content| self.store_content(content))
         } else {
             None
         };
@@ -155,30 +389,297 @@ impl EventLogger {
             path: self.to_relative_path(path),
             size,
             is_dir,
-            content,
+            loc: 0,
+            content_hash,
+            branch: None,
+            commit: None,
+            author: None,
+            author_email: None,
+            commit_subject: None,
+            staged: None,
+            unstaged: None,
+            name: None,
+            from_path: None,
+            similarity: None,
+            staged_change: None,
+        };
+        self.events.push(event);
+        self.persist_new_event();
+    }
+
+    /// Log a synthetic git-state event (a branch switch or new commit) into
+    /// the timeline alongside file events. `path` identifies which
+    /// repository or worktree it came from.
+    pub fn log_git_event(
+        &mut self,
+        path: &Path,
+        branch: String,
+        commit: String,
+        staged: usize,
+        unstaged: usize,
+    ) {
+        let timestamp = self.next_timestamp();
+
+        let event = FileEvent {
+            timestamp,
+            event_type: EventType::Git,
+            path: self.to_relative_path(path),
+            size: 0,
+            is_dir: false,
+            loc: 0,
+            content_hash: None,
+            branch: Some(branch),
+            commit: Some(commit),
+            author: None,
+            author_email: None,
+            commit_subject: None,
+            staged: Some(staged),
+            unstaged: Some(unstaged),
+            name: None,
+            from_path: None,
+            similarity: None,
+            staged_change: None,
         };
         self.events.push(event);
+        self.persist_new_event();
+    }
+
+    /// Log a synthetic repo-timeline event -- a commit, branch switch, or
+    /// checkout detected from a shallow watch of `.git/HEAD`/`.git/refs`
+    /// rather than from polling `git` directly (see [`log_git_event`] for
+    /// that source). `path` is the repository root the event came from.
+    ///
+    /// [`log_git_event`]: Self::log_git_event
+    pub fn log_repo_event(&mut self, event_type: EventType, path: &Path, branch: String, commit: String) {
+        let timestamp = self.next_timestamp();
+
+        let event = FileEvent {
+            timestamp,
+            event_type,
+            path: self.to_relative_path(path),
+            size: 0,
+            is_dir: false,
+            loc: 0,
+            content_hash: None,
+            branch: Some(branch),
+            commit: Some(commit),
+            author: None,
+            author_email: None,
+            commit_subject: None,
+            staged: None,
+            unstaged: None,
+            name: None,
+            from_path: None,
+            similarity: None,
+            staged_change: None,
+        };
+        self.events.push(event);
+        self.persist_new_event();
+    }
+
+    /// Log a named bookmark at the current instant, so recipients can
+    /// jump to moments of interest without scrubbing the whole timeline.
+    pub fn log_bookmark(&mut self, name: String) {
+        let timestamp = self.next_timestamp();
+
+        let event = FileEvent {
+            timestamp,
+            event_type: EventType::Bookmark,
+            path: String::new(),
+            size: 0,
+            is_dir: false,
+            loc: 0,
+            content_hash: None,
+            branch: None,
+            commit: None,
+            author: None,
+            author_email: None,
+            commit_subject: None,
+            staged: None,
+            unstaged: None,
+            name: Some(name),
+            from_path: None,
+            similarity: None,
+            staged_change: None,
+        };
+        self.events.push(event);
+        self.persist_new_event();
+    }
+
+    /// Finalize the recording: a final atomic rewrite in
+    /// [`RecordingFormat::Atomic`] mode, or a flush plus an informational
+    /// `"record":"trailer"` line (carrying [`RecordingStats`], ignored by
+    /// [`load_from_file`](Self::load_from_file)) in
+    /// [`RecordingFormat::Streaming`] mode.
+    pub fn finalize(&mut self) {
+        if self.output_path.is_none() {
+            return;
+        }
+        match self.format {
+            RecordingFormat::Atomic => self.write_file(),
+            RecordingFormat::Streaming => {
+                let stats = self.get_statistics();
+                let trailer = json!({
+                    "record": "trailer",
+                    "stats": {
+                        "total_events": stats.total_events,
+                        "created": stats.created,
+                        "modified": stats.modified,
+                        "deleted": stats.deleted,
+                        "duration_seconds": stats.duration_seconds,
+                    },
+                });
+                self.write_line(&trailer);
+            }
+        }
+    }
+
+    /// Reconstruct the state of the tree after folding `initial_state` and
+    /// every recorded `event` forward, as absolute paths rooted at
+    /// `root_path`. Used by `--resume` to seed a tracker with what this
+    /// recording already knows about before diffing it against a fresh scan.
+    pub fn fold_state(&self, root_path: &Path) -> HashMap<PathBuf, FileInfo> {
+        let mut state = HashMap::new();
+        for item in &self.initial_state {
+            let path_str = item.get("path").and_then(|v| v.as_str()).unwrap_or(".");
+            let size = item.get("size").and_then(|v| v.as_u64()).unwrap_or(0);
+            let is_dir = item
+                .get("is_dir")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let full_path = root_path.join(path_str);
+            state.insert(
+                full_path.clone(),
+                FileInfo {
+                    path: full_path,
+                    size,
+                    modified: 0.0,
+                    is_dir,
+                    loc: 0,
+                    git_status: None,
+                },
+            );
+        }
 
-        // Write complete valid JSON file after each event
-        if self.output_path.is_some() {
-            self.write_file();
+        for event in &self.events {
+            let full_path = root_path.join(&event.path);
+            match event.event_type {
+                EventType::Created => {
+                    state.insert(
+                        full_path.clone(),
+                        FileInfo {
+                            path: full_path,
+                            size: event.size,
+                            modified: 0.0,
+                            is_dir: event.is_dir,
+                            loc: event.loc,
+                            git_status: None,
+                        },
+                    );
+                }
+                EventType::Modified => {
+                    if let Some(info) = state.get_mut(&full_path) {
+                        info.size = event.size;
+                        info.loc = event.loc;
+                    }
+                }
+                EventType::Deleted => {
+                    state.remove(&full_path);
+                }
+                EventType::Renamed => {
+                    // Move the existing entry to its new path, preserving
+                    // size/loc, rather than dropping and recreating it --
+                    // that's the whole point of tracking renames distinctly
+                    // from a delete+create pair.
+                    let from_path = event.from_path.as_ref().map(|p| root_path.join(p));
+                    let mut info = from_path
+                        .and_then(|p| state.remove(&p))
+                        .unwrap_or(FileInfo {
+                            path: full_path.clone(),
+                            size: event.size,
+                            modified: 0.0,
+                            is_dir: event.is_dir,
+                            loc: event.loc,
+                            git_status: None,
+                        });
+                    info.path = full_path.clone();
+                    state.insert(full_path, info);
+                }
+                EventType::Copied => {
+                    // The origin keeps existing, so only insert the new
+                    // entry at the destination.
+                    state.insert(
+                        full_path.clone(),
+                        FileInfo {
+                            path: full_path,
+                            size: event.size,
+                            modified: 0.0,
+                            is_dir: event.is_dir,
+                            loc: event.loc,
+                            git_status: None,
+                        },
+                    );
+                }
+                EventType::Git
+                | EventType::Bookmark
+                | EventType::StatusChanged
+                | EventType::Commit
+                | EventType::BranchChanged
+                | EventType::Checkout => {}
+            }
         }
+
+        state
     }
 
-    /// Finalize the recording (writes final state).
-    pub fn finalize(&self) {
-        if self.output_path.is_some() {
-            self.write_file();
+    /// Resume an existing recording file: load its accumulated
+    /// `initial_state` and `events`, keep the original `start_time` so new
+    /// events continue the same timeline (timestamps are always
+    /// `now - start_time`, so this naturally folds in the real wall-clock
+    /// gap since the file was last appended to), and point future writes
+    /// back at the same file.
+    pub fn resume_from_file(
+        filepath: &Path,
+        root_path: Option<PathBuf>,
+        record_content: bool,
+    ) -> Result<Self> {
+        let mut logger = Self::load_from_file(filepath)?;
+        if logger.start_time.is_none() {
+            anyhow::bail!(
+                "{} has no start_time recorded, cannot resume",
+                filepath.display()
+            );
+        }
+        logger.output_path = Some(filepath.to_path_buf());
+        logger.root_path = root_path;
+        logger.record_content = record_content;
+
+        // In streaming mode the header and every event so far are already
+        // on disk -- reopen for appending rather than truncating, and mark
+        // every blob already loaded as flushed so it isn't written twice.
+        if logger.format == RecordingFormat::Streaming {
+            logger.open_writer(true);
+            logger.flushed_blobs = logger.blobs.keys().cloned().collect();
         }
+        Ok(logger)
     }
 
-    /// Load a recording from a JSON file.
+    /// Load a recording from a file, detecting whether it's a single
+    /// [`RecordingFormat::Atomic`] JSON document or line-delimited
+    /// [`RecordingFormat::Streaming`] NDJSON.
     pub fn load_from_file(filepath: &Path) -> Result<Self> {
         let contents = fs::read_to_string(filepath)
             .with_context(|| format!("reading {}", filepath.display()))?;
-        let data: Value =
-            serde_json::from_str(&contents).with_context(|| "parsing recording JSON")?;
 
+        match serde_json::from_str::<Value>(&contents) {
+            Ok(data) => Self::from_atomic_json(data),
+            Err(_) => Self::from_ndjson(&contents),
+        }
+    }
+
+    /// Reconstruct a logger from a single-document [`RecordingFormat::Atomic`]
+    /// recording.
+    fn from_atomic_json(data: Value) -> Result<Self> {
         let start_time = data.get("start_time").and_then(|v| v.as_f64());
 
         let initial_state: Vec<Value> = data
@@ -193,6 +694,71 @@ impl EventLogger {
             .map(|arr| arr.iter().filter_map(FileEvent::from_json).collect())
             .unwrap_or_default();
 
+        let blobs: HashMap<String, String> = data
+            .get("blobs")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+
+        Ok(Self {
+            events,
+            initial_state,
+            start_time,
+            output_path: None,
+            root_path: None,
+            record_content: false,
+            blobs,
+            format: RecordingFormat::Atomic,
+            writer: None,
+            flushed_blobs: HashSet::new(),
+            gitignore: None,
+        })
+    }
+
+    /// Reconstruct a logger from a line-delimited [`RecordingFormat::Streaming`]
+    /// recording: a `"record":"header"` line, then one line per blob or
+    /// event in append order. A trailing `"record":"trailer"` line (if
+    /// present) is ignored -- its stats are always recomputed from `events`.
+    fn from_ndjson(contents: &str) -> Result<Self> {
+        let mut start_time = None;
+        let mut initial_state = Vec::new();
+        let mut events = Vec::new();
+        let mut blobs = HashMap::new();
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let value: Value =
+                serde_json::from_str(line).with_context(|| "parsing recording NDJSON line")?;
+
+            match value.get("record").and_then(|v| v.as_str()) {
+                Some("header") => {
+                    start_time = value.get("start_time").and_then(|v| v.as_f64());
+                    initial_state = value
+                        .get("initial_state")
+                        .and_then(|v| v.as_array())
+                        .cloned()
+                        .unwrap_or_default();
+                }
+                Some("blob") => {
+                    if let (Some(hash), Some(content)) = (
+                        value.get("hash").and_then(|v| v.as_str()),
+                        value.get("content").and_then(|v| v.as_str()),
+                    ) {
+                        blobs.insert(hash.to_string(), content.to_string());
+                    }
+                }
+                Some("trailer") => {}
+                _ => {
+                    if let Some(event) = FileEvent::from_json(&value) {
+                        events.push(event);
+                    }
+                }
+            }
+        }
+
+        let flushed_blobs = blobs.keys().cloned().collect();
         Ok(Self {
             events,
             initial_state,
@@ -200,6 +766,11 @@ impl EventLogger {
             output_path: None,
             root_path: None,
             record_content: false,
+            blobs,
+            format: RecordingFormat::Streaming,
+            writer: None,
+            flushed_blobs,
+            gitignore: None,
         })
     }
 
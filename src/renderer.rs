@@ -3,30 +3,298 @@
 //! This module is purely presentational -- it takes references to application
 //! data and renders into a Ratatui `Frame`.  It does **not** own any state.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::{Block, Borders, Paragraph};
 use ratatui::Frame;
+use rayon::prelude::*;
 use unicode_width::UnicodeWidthStr;
 
+use crate::diagnostics::LogEntry;
 use crate::state::{
-    format_delta, format_loc, format_size, get_file_emoji, get_size_color, ChangeSet, FileInfo,
+    format_delta_with, format_loc, format_relative_time, format_size_with, get_file_emoji,
+    get_size_color, ChangeSet, FileInfo, SizeFormat,
 };
 use crate::statistics::{StatisticsTracker, Stats};
+use crate::theme::Theme;
 
 // ---------------------------------------------------------------------------
 // Constants
 // ---------------------------------------------------------------------------
 
-const NAME_WIDTH: u16 = 42;
 const STATUS_WIDTH: u16 = 10;
-const SIZE_WIDTH: u16 = 10;
-const DELTA_WIDTH: u16 = 10;
-const LOC_WIDTH: u16 = 8;
+const BAR_WIDTH: u16 = 10;
+
+/// Floor for the Name column so `compute_column_widths` never clamps it to
+/// something unreadable on a very narrow terminal.
+const MIN_NAME_WIDTH: usize = 12;
+
+/// Below this many tracked entries, `build_tree` and `render_summary_line`
+/// walk `state` on a single thread -- spinning up rayon's thread pool costs
+/// more than it saves for the typical small-to-medium project. Past it,
+/// exa-style scoped parallelism takes over for both the tree build and the
+/// summary totals pass, keeping redraws responsive on huge monorepos.
+const PARALLEL_TREE_THRESHOLD: usize = 10_000;
+
+// ---------------------------------------------------------------------------
+// Column widths
+// ---------------------------------------------------------------------------
+
+/// Per-render column widths, measured from the widest visible cell rather
+/// than fixed at compile time, exa `details.rs`-style. See
+/// `compute_column_widths`.
+#[derive(Clone, Copy, Debug)]
+pub struct ColumnWidths {
+    pub name: usize,
+    pub size: usize,
+    pub delta: usize,
+    pub loc: usize,
+    pub modified: usize,
+    pub churn: usize,
+    /// Whether the opt-in `--long` Modified/Churn columns fit `area_width`
+    /// this render and should be drawn. Set by `compute_column_widths`,
+    /// which silently clears this back to `false` when the terminal is too
+    /// narrow even if the caller requested them, so `tree_column_headers`
+    /// and `render_tree_lines` can trust it instead of re-checking width.
+    pub show_details: bool,
+}
+
+/// Measure the widest name+prefix, formatted size, delta, LOC, and (if
+/// `want_details`) Modified/Churn cell among the rows that will actually be
+/// drawn, by walking the same viewport traversal bounds as
+/// `count_tree_lines`/`max_visible_size`. Each width starts at its column
+/// header's own width so a shallow tree with short names still lines up
+/// under "Name"/"Size"/etc.
+///
+/// `area_width` then clamps the Name column so the total row -- name +
+/// status + size + delta + loc*2 + (modified + churn, if they fit) + bar,
+/// plus separating spaces -- fits the terminal `Rect`. Name never drops
+/// below `MIN_NAME_WIDTH`, so on a terminal narrower than the other columns
+/// plus that floor the row is still wider than `area_width`. If adding the detail columns would squeeze Name below
+/// `MIN_NAME_WIDTH`, `show_details` is cleared and the row collapses back to
+/// the compact layout instead.
+///
+/// `now` is the render's current unix-epoch time, used to format the
+/// Modified column's "Nm/h/d ago" cells; it's threaded in by the caller
+/// (rather than read here via `SystemTime::now()`) so a single render uses
+/// one consistent "now" across every row.
+#[allow(clippy::too_many_arguments)]
+pub fn compute_column_widths(
+    nodes: &[TreeNode],
+    state: &HashMap<PathBuf, FileInfo>,
+    previous_state: &HashMap<PathBuf, FileInfo>,
+    max_depth: Option<usize>,
+    max_files: Option<usize>,
+    visible_start: usize,
+    visible_end: usize,
+    area_width: u16,
+    collapsed: &HashSet<PathBuf>,
+    size_format: SizeFormat,
+    want_details: bool,
+    now: f64,
+) -> ColumnWidths {
+    let mut widths = ColumnWidths {
+        name: UnicodeWidthStr::width("Name"),
+        size: UnicodeWidthStr::width("Size"),
+        delta: UnicodeWidthStr::width("Delta"),
+        loc: UnicodeWidthStr::width("LOC").max(UnicodeWidthStr::width("LOC+/-")),
+        modified: UnicodeWidthStr::width("Modified"),
+        churn: UnicodeWidthStr::width("Churn"),
+        show_details: want_details,
+    };
+
+    let mut line_index = 0usize;
+    measure_column_widths(
+        nodes,
+        "",
+        state,
+        previous_state,
+        max_depth,
+        max_files,
+        0,
+        visible_start,
+        visible_end,
+        &mut line_index,
+        &mut widths,
+        collapsed,
+        size_format,
+        want_details,
+        now,
+    );
+
+    let base_fixed_width = 1 // leading space before the name column
+        + STATUS_WIDTH as usize
+        + widths.size
+        + widths.delta
+        + widths.loc
+        + widths.loc
+        + 1 // leading space before the usage bar
+        + BAR_WIDTH as usize;
+    let details_width = 1 + widths.modified + 1 + widths.churn; // leading space before each
+
+    if want_details {
+        let available = (area_width as usize).saturating_sub(base_fixed_width + details_width);
+        if available < MIN_NAME_WIDTH {
+            widths.show_details = false;
+        }
+    }
+
+    let fixed_width = base_fixed_width + if widths.show_details { details_width } else { 0 };
+    let available = (area_width as usize).saturating_sub(fixed_width);
+    widths.name = widths.name.min(available).max(MIN_NAME_WIDTH);
+
+    widths
+}
+
+/// Recursive helper behind `compute_column_widths`; mirrors
+/// `render_tree_lines`'s traversal (prefix/connector construction, viewport
+/// skip-ahead) but only measures cell widths instead of building `Line`s.
+#[allow(clippy::too_many_arguments)]
+fn measure_column_widths(
+    nodes: &[TreeNode],
+    prefix: &str,
+    state: &HashMap<PathBuf, FileInfo>,
+    previous_state: &HashMap<PathBuf, FileInfo>,
+    max_depth: Option<usize>,
+    max_files: Option<usize>,
+    current_depth: usize,
+    visible_start: usize,
+    visible_end: usize,
+    line_index: &mut usize,
+    widths: &mut ColumnWidths,
+    collapsed: &HashSet<PathBuf>,
+    size_format: SizeFormat,
+    want_details: bool,
+    now: f64,
+) {
+    if let Some(md) = max_depth {
+        if current_depth > md {
+            *line_index += 1;
+            return;
+        }
+    }
+
+    let total = nodes.len();
+    let display_count = match max_files {
+        Some(mf) => mf.min(total),
+        None => total,
+    };
+
+    for (i, node) in nodes.iter().enumerate() {
+        if *line_index >= visible_end {
+            return;
+        }
+
+        if i >= display_count {
+            *line_index += 1;
+            break;
+        }
+
+        let is_last = i == total - 1;
+        let connector = if is_last { "└── " } else { "├── " };
+        let child_prefix = if is_last {
+            format!("{}    ", prefix)
+        } else {
+            format!("{}│   ", prefix)
+        };
+
+        let visible = *line_index >= visible_start && *line_index < visible_end;
+        if visible {
+            let indicator = if node.is_dir && !node.children.is_empty() {
+                if collapsed.contains(&node.path) {
+                    "▶ "
+                } else {
+                    "▼ "
+                }
+            } else {
+                ""
+            };
+            let emoji = get_file_emoji(&node.name, node.is_dir);
+            let name_cell = format!(
+                "{}{}{}{} {}",
+                prefix, connector, indicator, emoji, node.name
+            );
+            widths.name = widths.name.max(UnicodeWidthStr::width(name_cell.as_str()));
+
+            if !node.is_dir {
+                if let Some(agg) = &node.aggregated {
+                    widths.size = widths.size.max(UnicodeWidthStr::width(
+                        format_size_with(agg.size, size_format).as_str(),
+                    ));
+                    widths.loc = widths
+                        .loc
+                        .max(UnicodeWidthStr::width(format_loc(agg.loc).as_str()));
+                } else if let Some(info) = state.get(&node.path) {
+                    widths.size = widths.size.max(UnicodeWidthStr::width(
+                        format_size_with(info.size, size_format).as_str(),
+                    ));
+
+                    let prev_size = previous_state.get(&node.path).map(|p| p.size).unwrap_or(0);
+                    let (delta_str, _) =
+                        format_delta_with(info.size as i64 - prev_size as i64, true, size_format);
+                    widths.delta = widths.delta.max(UnicodeWidthStr::width(delta_str.as_str()));
+
+                    widths.loc = widths
+                        .loc
+                        .max(UnicodeWidthStr::width(format_loc(info.loc).as_str()));
+
+                    let prev_loc = previous_state.get(&node.path).map(|p| p.loc).unwrap_or(0);
+                    let loc_delta = info.loc as i64 - prev_loc as i64;
+                    let (loc_delta_str, _) = format_delta_with(loc_delta, false, size_format);
+                    widths.loc = widths
+                        .loc
+                        .max(UnicodeWidthStr::width(loc_delta_str.as_str()));
+
+                    if want_details {
+                        widths.modified = widths.modified.max(UnicodeWidthStr::width(
+                            format_relative_time(now, info.modified).as_str(),
+                        ));
+                        widths.churn = widths.churn.max(UnicodeWidthStr::width(
+                            format_loc(loc_delta.unsigned_abs() as usize).as_str(),
+                        ));
+                    }
+                }
+            }
+        }
+        *line_index += 1;
+
+        if node.is_dir && !node.children.is_empty() && !collapsed.contains(&node.path) {
+            let subtree_size = count_tree_lines(
+                &node.children,
+                max_depth,
+                max_files,
+                current_depth + 1,
+                collapsed,
+            );
+            if *line_index + subtree_size <= visible_start {
+                *line_index += subtree_size;
+            } else {
+                measure_column_widths(
+                    &node.children,
+                    &child_prefix,
+                    state,
+                    previous_state,
+                    max_depth,
+                    max_files,
+                    current_depth + 1,
+                    visible_start,
+                    visible_end,
+                    line_index,
+                    widths,
+                    collapsed,
+                    size_format,
+                    want_details,
+                    now,
+                );
+            }
+        }
+    }
+}
 
 // ---------------------------------------------------------------------------
 // TreeNode
@@ -39,6 +307,18 @@ pub struct TreeNode {
     pub path: PathBuf,
     pub is_dir: bool,
     pub children: Vec<TreeNode>,
+    /// Set on the synthetic "N small files" row `build_from_map`'s
+    /// size-threshold aggregation produces in place of two or more files
+    /// below the threshold. `None` for every real file or directory, whose
+    /// size/LOC are looked up from the live `state` map instead.
+    pub aggregated: Option<AggregatedInfo>,
+}
+
+/// Summed size/LOC for a synthetic aggregate row.
+#[derive(Clone, Copy)]
+pub struct AggregatedInfo {
+    pub size: u64,
+    pub loc: usize,
 }
 
 // ---------------------------------------------------------------------------
@@ -46,19 +326,48 @@ pub struct TreeNode {
 // ---------------------------------------------------------------------------
 
 /// Map a color name (as returned by helpers such as `get_size_color` /
-/// `format_delta`) to a Ratatui `Color`.
-fn color_from_name(name: &str) -> Color {
-    match name {
-        "dim" => Color::DarkGray,
-        "cyan" => Color::Cyan,
-        "yellow" => Color::Yellow,
-        "red" => Color::Red,
-        "green" => Color::Green,
-        "blue" => Color::Blue,
-        "magenta" => Color::Magenta,
-        "white" => Color::White,
-        _ => Color::Reset,
+/// `format_delta`) to a Ratatui `Color`. Moved to `crate::theme` alongside
+/// the `Theme` struct it now also backs; re-exported here under its
+/// original name since every call site in this file predates the theme.
+use crate::theme::color_from_name;
+
+/// Split the accumulated box-drawing `prefix` into its 4-character-wide
+/// indentation segments (one per ancestor depth, each a `"│   "` or
+/// `"    "`) plus the connector for this node, coloring every segment by
+/// its nesting depth -- cycling through `palette` (see
+/// `Theme::rainbow_palette_colors`) like lsd's `TreeEdge` coloring -- when
+/// `rainbow` is enabled, or uniformly `Color::DarkGray` otherwise.
+fn prefix_spans(
+    prefix: &str,
+    connector: &str,
+    current_depth: usize,
+    rainbow: bool,
+    palette: &[Color],
+) -> Vec<Span<'static>> {
+    if !rainbow {
+        return vec![Span::styled(
+            format!("{}{}", prefix, connector),
+            Style::default().fg(Color::DarkGray),
+        )];
     }
+
+    // Chunk by *characters*, not bytes -- "│" is multi-byte but one column
+    // wide, so a byte-based chunk would split it.
+    let chars: Vec<char> = prefix.chars().collect();
+    let mut spans: Vec<Span<'static>> = chars
+        .chunks(4)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let segment: String = chunk.iter().collect();
+            Span::styled(segment, Style::default().fg(palette[i % palette.len()]))
+        })
+        .collect();
+
+    spans.push(Span::styled(
+        connector.to_string(),
+        Style::default().fg(palette[current_depth % palette.len()]),
+    ));
+    spans
 }
 
 // ---------------------------------------------------------------------------
@@ -67,10 +376,22 @@ fn color_from_name(name: &str) -> Color {
 
 /// Build a sorted tree of `TreeNode`s from the flat state map.
 ///
-/// Only direct children of each directory are included.  Directories are sorted
-/// before files; within each group entries are sorted alphabetically
-/// (case-insensitive).
-pub fn build_tree(root: &Path, state: &HashMap<PathBuf, FileInfo>) -> Vec<TreeNode> {
+/// Only direct children of each directory are included.  Directories are
+/// always sorted before files; within each group entries are ordered by
+/// `sort` (see `SortKind`), reversed if `reverse` is set.
+///
+/// `aggregate_threshold`, if set, collapses files below that byte size into
+/// a single synthetic "N small files" row per directory, `dutree --aggr`
+/// style -- see `build_from_map`.
+#[allow(clippy::too_many_arguments)]
+pub fn build_tree(
+    root: &Path,
+    state: &HashMap<PathBuf, FileInfo>,
+    aggregate_threshold: Option<u64>,
+    sort: SortKind,
+    reverse: bool,
+    changes: &ChangeSet,
+) -> Vec<TreeNode> {
     // Single-pass: group all entries by their parent directory.
     let mut children_map: HashMap<PathBuf, Vec<(PathBuf, bool)>> = HashMap::new();
 
@@ -83,56 +404,263 @@ pub fn build_tree(root: &Path, state: &HashMap<PathBuf, FileInfo>) -> Vec<TreeNo
         }
     }
 
-    build_from_map(root, &children_map)
+    // Scoped parallel build (exa-style) past the threshold; the recursion
+    // below stays identical either way, just switching which iterator drives
+    // per-entry construction.
+    let parallel = state.len() > PARALLEL_TREE_THRESHOLD;
+
+    build_from_map(
+        root,
+        &children_map,
+        state,
+        aggregate_threshold,
+        sort,
+        reverse,
+        changes,
+        parallel,
+    )
 }
 
 /// Recursively build tree nodes from the pre-computed children map.
+///
+/// `parallel` selects between a sequential `.iter()` walk and a rayon
+/// `par_iter()` walk for constructing this level's entries; `par_iter()`'s
+/// `collect()` preserves input order, so the result is identical either way
+/// and `sort_group` below stays deterministic regardless of which path ran.
+#[allow(clippy::too_many_arguments)]
 fn build_from_map(
     parent: &Path,
     children_map: &HashMap<PathBuf, Vec<(PathBuf, bool)>>,
+    state: &HashMap<PathBuf, FileInfo>,
+    aggregate_threshold: Option<u64>,
+    sort: SortKind,
+    reverse: bool,
+    changes: &ChangeSet,
+    parallel: bool,
 ) -> Vec<TreeNode> {
     let Some(entries) = children_map.get(parent) else {
         return Vec::new();
     };
 
-    let mut dirs: Vec<TreeNode> = Vec::new();
-    let mut files: Vec<TreeNode> = Vec::new();
-
-    for (path, is_dir) in entries {
+    let build_node = |(path, is_dir): &(PathBuf, bool)| -> TreeNode {
         let name = path
             .file_name()
             .map(|n| n.to_string_lossy().to_string())
             .unwrap_or_default();
 
         let children = if *is_dir {
-            build_from_map(path, children_map)
+            build_from_map(
+                path,
+                children_map,
+                state,
+                aggregate_threshold,
+                sort,
+                reverse,
+                changes,
+                parallel,
+            )
         } else {
             Vec::new()
         };
 
-        let node = TreeNode {
+        TreeNode {
             name,
             path: path.clone(),
             is_dir: *is_dir,
             children,
-        };
-
-        if *is_dir {
-            dirs.push(node);
-        } else {
-            files.push(node);
+            aggregated: None,
         }
-    }
+    };
+
+    let built: Vec<TreeNode> = if parallel {
+        entries.par_iter().map(build_node).collect()
+    } else {
+        entries.iter().map(build_node).collect()
+    };
+
+    let (mut dirs, mut files): (Vec<TreeNode>, Vec<TreeNode>) =
+        built.into_iter().partition(|node| node.is_dir);
 
-    // Sort each group alphabetically (case-insensitive).
-    dirs.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-    files.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    // Each group is ordered independently by the active sort metric; the
+    // directory/file grouping itself always stays dirs-before-files.
+    sort_group(&mut dirs, state, changes, sort, reverse);
+    sort_group(&mut files, state, changes, sort, reverse);
+
+    if let Some(threshold) = aggregate_threshold {
+        files = aggregate_small_files(parent, files, state, threshold);
+    }
 
     // Directories first, then files.
     dirs.extend(files);
     dirs
 }
 
+// ---------------------------------------------------------------------------
+// Tree sorting
+// ---------------------------------------------------------------------------
+
+/// Which metric siblings are ordered by when building the tree, `fm`/`exa`
+/// sort-mode style. Directories are always grouped before files within a
+/// level (see `build_from_map`); within each group, entries are ordered by
+/// this metric -- for a directory, by the aggregated metric of its entire
+/// subtree, so e.g. the heaviest directory still bubbles up under `Size`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SortKind {
+    /// Case-insensitive alphabetical. The default, and the original (only)
+    /// ordering before sort modes existed.
+    #[default]
+    Name,
+    /// Descending by byte size (subtree total for directories).
+    Size,
+    /// Descending by line count (subtree total for directories).
+    Loc,
+    /// Entries touched by the current change set (see `ChangeSet`) bubble to
+    /// the top; a directory counts as touched if anything in its subtree is.
+    Churn,
+    /// Descending by modification time (latest descendant for directories).
+    Recent,
+}
+
+impl SortKind {
+    /// The next mode in the cycle order bound to the `s` key, wrapping back
+    /// to `Name` after the last one.
+    pub fn next(self) -> Self {
+        match self {
+            SortKind::Name => SortKind::Size,
+            SortKind::Size => SortKind::Loc,
+            SortKind::Loc => SortKind::Churn,
+            SortKind::Churn => SortKind::Recent,
+            SortKind::Recent => SortKind::Name,
+        }
+    }
+}
+
+/// Total LOC of a tree node -- the `Loc` sort metric's analogue of
+/// `node_size`.
+fn node_loc(node: &TreeNode, state: &HashMap<PathBuf, FileInfo>) -> usize {
+    if let Some(agg) = &node.aggregated {
+        agg.loc
+    } else if node.is_dir {
+        node.children.iter().map(|c| node_loc(c, state)).sum()
+    } else {
+        state.get(&node.path).map(|info| info.loc).unwrap_or(0)
+    }
+}
+
+/// Latest modification time in a node's subtree -- the `Recent` sort
+/// metric's analogue of `node_size`. Aggregate rows and directories/files
+/// missing from `state` sort to the very back.
+fn node_recent(node: &TreeNode, state: &HashMap<PathBuf, FileInfo>) -> f64 {
+    if node.aggregated.is_some() {
+        f64::MIN
+    } else if node.is_dir {
+        node.children
+            .iter()
+            .map(|c| node_recent(c, state))
+            .fold(f64::MIN, f64::max)
+    } else {
+        state
+            .get(&node.path)
+            .map(|info| info.modified)
+            .unwrap_or(f64::MIN)
+    }
+}
+
+/// Whether a node (or, for a directory, anything in its subtree) was added
+/// or modified in the current change set -- the `Churn` sort metric.
+fn node_churned(node: &TreeNode, changes: &ChangeSet) -> bool {
+    if node.is_dir {
+        node.children.iter().any(|c| node_churned(c, changes))
+    } else {
+        changes.added.contains(&node.path) || changes.modified.contains(&node.path)
+    }
+}
+
+/// Order one already-split `dirs`/`files` group by `sort`. `Name` keeps the
+/// original case-insensitive alphabetical sort; every other mode sorts
+/// descending by its metric (so the biggest/most-recent/most-touched entry
+/// comes first), with a name tiebreak for equal metrics, then reverses the
+/// whole group if `reverse` is set.
+fn sort_group(
+    group: &mut [TreeNode],
+    state: &HashMap<PathBuf, FileInfo>,
+    changes: &ChangeSet,
+    sort: SortKind,
+    reverse: bool,
+) {
+    match sort {
+        SortKind::Name => {
+            group.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        }
+        SortKind::Churn => {
+            group.sort_by(|a, b| {
+                let ac = node_churned(a, changes);
+                let bc = node_churned(b, changes);
+                bc.cmp(&ac)
+                    .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+            });
+        }
+        SortKind::Size | SortKind::Loc | SortKind::Recent => {
+            group.sort_by(|a, b| {
+                let (ak, bk) = match sort {
+                    SortKind::Size => (node_size(a, state) as f64, node_size(b, state) as f64),
+                    SortKind::Loc => (node_loc(a, state) as f64, node_loc(b, state) as f64),
+                    SortKind::Recent => (node_recent(a, state), node_recent(b, state)),
+                    SortKind::Name | SortKind::Churn => unreachable!(),
+                };
+                bk.partial_cmp(&ak)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+            });
+        }
+    }
+
+    if reverse {
+        group.reverse();
+    }
+}
+
+/// Collapse files below `threshold` bytes into one synthetic "N small
+/// files" row carrying their summed size/LOC, like `dutree`'s `--aggr`
+/// option. Leaves `files` untouched if fewer than two fall below the
+/// threshold -- aggregating a single file wouldn't save any space.
+fn aggregate_small_files(
+    parent: &Path,
+    files: Vec<TreeNode>,
+    state: &HashMap<PathBuf, FileInfo>,
+    threshold: u64,
+) -> Vec<TreeNode> {
+    let (mut kept, small): (Vec<TreeNode>, Vec<TreeNode>) = files
+        .into_iter()
+        .partition(|node| state.get(&node.path).map(|info| info.size).unwrap_or(0) >= threshold);
+
+    if small.len() < 2 {
+        kept.extend(small);
+        return kept;
+    }
+
+    let mut agg_size = 0u64;
+    let mut agg_loc = 0usize;
+    for node in &small {
+        if let Some(info) = state.get(&node.path) {
+            agg_size += info.size;
+            agg_loc += info.loc;
+        }
+    }
+
+    kept.push(TreeNode {
+        name: format!("{} small files", small.len()),
+        path: parent.join(format!("__{}_small_files__", small.len())),
+        is_dir: false,
+        children: Vec::new(),
+        aggregated: Some(AggregatedInfo {
+            size: agg_size,
+            loc: agg_loc,
+        }),
+    });
+    kept
+}
+
 // ---------------------------------------------------------------------------
 // Tree filtering
 // ---------------------------------------------------------------------------
@@ -166,6 +694,7 @@ fn filter_node(node: &TreeNode, query_lower: &str) -> Option<TreeNode> {
                 path: node.path.clone(),
                 is_dir: node.is_dir,
                 children: filtered_children,
+                aggregated: node.aggregated,
             })
         } else {
             None
@@ -176,6 +705,7 @@ fn filter_node(node: &TreeNode, query_lower: &str) -> Option<TreeNode> {
             path: node.path.clone(),
             is_dir: node.is_dir,
             children: Vec::new(),
+            aggregated: node.aggregated,
         })
     } else {
         None
@@ -210,6 +740,27 @@ fn filter_node(node: &TreeNode, query_lower: &str) -> Option<TreeNode> {
 /// * `visible_end`    - Last visible line index (exclusive).
 /// * `line_index`     - Global line counter (mutated during traversal).
 /// * `lines`          - Output vector to which rendered `Line`s are appended.
+/// * `max_visible_size` - Largest node size in the current viewport (see
+///   `max_visible_size`), the denominator for the proportional usage bar
+///   column.
+/// * `rainbow_edges`  - Color the indentation guides by nesting depth (see
+///   `Theme::rainbow_palette_colors`) instead of uniform `Color::DarkGray`.
+/// * `widths`         - Content-measured column widths for this render (see
+///   `compute_column_widths`), replacing the old fixed-width constants.
+///   `widths.show_details` gates the opt-in Modified/Churn columns below.
+/// * `selected`       - Path of the currently focused node, if any; its name
+///   span is rendered with `Modifier::REVERSED` and a distinct background,
+///   and row auto-scroll (see `App::sync_scroll_to_selection`) keeps it
+///   within the viewport.
+/// * `theme`          - Semantic color roles for created/modified/deleted
+///   status and directory names (see `crate::theme::Theme`).
+/// * `collapsed`      - Directories whose children are hidden, each drawn
+///   with a `▶` indicator instead of recursing; expanded directories with
+///   children draw `▼`. Toggled per-path by the caller (e.g. Enter on the
+///   selected row).
+/// * `now`            - Render's current unix-epoch time, passed through to
+///   `format_relative_time` for the Modified column so every row (and the
+///   width pass in `compute_column_widths`) agrees on one "now".
 #[allow(clippy::too_many_arguments)]
 pub fn render_tree_lines(
     nodes: &[TreeNode],
@@ -224,6 +775,14 @@ pub fn render_tree_lines(
     visible_end: usize,
     line_index: &mut usize,
     lines: &mut Vec<Line<'static>>,
+    max_visible_size: u64,
+    rainbow_edges: bool,
+    widths: &ColumnWidths,
+    selected: Option<&Path>,
+    theme: &Theme,
+    collapsed: &HashSet<PathBuf>,
+    size_format: SizeFormat,
+    now: f64,
 ) {
     // If we have exceeded the maximum depth, emit a placeholder and return.
     if let Some(md) = max_depth {
@@ -245,6 +804,8 @@ pub fn render_tree_lines(
         None => total,
     };
 
+    let rainbow_palette = theme.rainbow_palette_colors();
+
     for (i, node) in nodes.iter().enumerate() {
         // Early exit: all remaining lines are past the viewport.
         if *line_index >= visible_end {
@@ -284,40 +845,64 @@ pub fn render_tree_lines(
             // 1. Prefix + connector
             let prefix_str = format!("{}{}", prefix, connector);
             used_width += UnicodeWidthStr::width(prefix_str.as_str());
-            spans.push(Span::styled(
-                prefix_str,
-                Style::default().fg(Color::DarkGray),
+            spans.extend(prefix_spans(
+                prefix,
+                connector,
+                current_depth,
+                rainbow_edges,
+                &rainbow_palette,
             ));
 
-            // 2. Emoji
+            // 2. Collapse/expand indicator, directories with children only.
+            if node.is_dir && !node.children.is_empty() {
+                let indicator = if collapsed.contains(&node.path) {
+                    "▶ "
+                } else {
+                    "▼ "
+                };
+                used_width += UnicodeWidthStr::width(indicator);
+                spans.push(Span::styled(
+                    indicator,
+                    Style::default().fg(theme.legend_dim_color()),
+                ));
+            }
+
+            // 3. Emoji
             let emoji = get_file_emoji(&node.name, node.is_dir);
             let emoji_str = format!("{} ", emoji);
             used_width += UnicodeWidthStr::width(emoji_str.as_str());
             spans.push(Span::raw(emoji_str));
 
-            // 3. Name -- colored by change status
+            // 4. Name -- colored by change status
             let (name_color, status_text) = if changes.added.contains(&node.path) {
-                (Color::Green, "NEW")
+                (theme.created_color(), "NEW")
             } else if changes.modified.contains(&node.path) {
-                (Color::Yellow, "MOD")
+                (theme.modified_color(), "MOD")
             } else if changes.deleted.contains(&node.path) {
-                (Color::Red, "DEL")
+                (theme.deleted_color(), "DEL")
+            } else if node.is_dir {
+                (theme.dir_color(), "")
             } else {
                 (Color::White, "")
             };
 
-            let name_style = if node.is_dir {
+            let mut name_style = if node.is_dir {
                 Style::default().fg(name_color).add_modifier(Modifier::BOLD)
             } else {
                 Style::default().fg(name_color)
             };
+            if selected == Some(node.path.as_path()) {
+                name_style = name_style
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::REVERSED);
+            }
 
             used_width += UnicodeWidthStr::width(node.name.as_str());
             spans.push(Span::styled(node.name.clone(), name_style));
 
-            // 4. Pad the name portion to fill the Name column (1 + NAME_WIDTH),
+            // 5. Pad the name portion to fill the Name column (1 + widths.name),
             //    then render the status badge in the Status column.
-            let name_col_end = 1 + NAME_WIDTH as usize;
+            let name_col_end = 1 + widths.name;
             let name_pad = if used_width < name_col_end {
                 name_col_end - used_width
             } else {
@@ -325,12 +910,12 @@ pub fn render_tree_lines(
             };
             spans.push(Span::raw(" ".repeat(name_pad)));
 
-            // 5. Status badge — rendered at the start of the Status column.
+            // 6. Status badge — rendered at the start of the Status column.
             if !status_text.is_empty() {
                 let badge_color = match status_text {
-                    "NEW" => Color::Green,
-                    "MOD" => Color::Yellow,
-                    "DEL" => Color::Red,
+                    "NEW" => theme.created_color(),
+                    "MOD" => theme.modified_color(),
+                    "DEL" => theme.deleted_color(),
                     _ => Color::Reset,
                 };
                 let badge = format!("[{}]", status_text);
@@ -351,55 +936,125 @@ pub fn render_tree_lines(
             }
 
             // For files (not dirs), show size, delta, LOC, LOC delta as
-            // fixed-width right-aligned columns.
+            // fixed-width right-aligned columns. Aggregate "N small files"
+            // rows carry their own summed totals instead of a `state`
+            // lookup, and have no meaningful delta (they aren't a single
+            // tracked path), so those columns are left blank.
             if !node.is_dir {
-                if let Some(info) = state.get(&node.path) {
-                    // 6. Size (right-aligned, SIZE_WIDTH)
-                    let size_str = format_size(info.size);
+                if let Some(agg) = &node.aggregated {
+                    let size_str = format_size_with(agg.size, size_format);
+                    let size_color = color_from_name(get_size_color(agg.size));
+                    spans.push(Span::styled(
+                        format!("{:>width$}", size_str, width = widths.size),
+                        Style::default().fg(size_color),
+                    ));
+                    spans.push(Span::raw(" ".repeat(widths.delta)));
+
+                    let loc_str = format_loc(agg.loc);
+                    spans.push(Span::styled(
+                        format!("{:>width$}", loc_str, width = widths.loc),
+                        Style::default().fg(theme.legend_dim_color()),
+                    ));
+                    spans.push(Span::raw(" ".repeat(widths.loc)));
+
+                    // Aggregate rows have no single mtime/churn to show.
+                    if widths.show_details {
+                        spans.push(Span::raw(
+                            " ".repeat(1 + widths.modified + 1 + widths.churn),
+                        ));
+                    }
+                } else if let Some(info) = state.get(&node.path) {
+                    // 7. Size (right-aligned, widths.size)
+                    let size_str = format_size_with(info.size, size_format);
                     let size_color = color_from_name(get_size_color(info.size));
                     spans.push(Span::styled(
-                        format!("{:>width$}", size_str, width = SIZE_WIDTH as usize),
+                        format!("{:>width$}", size_str, width = widths.size),
                         Style::default().fg(size_color),
                     ));
 
-                    // 7. Size delta (right-aligned, DELTA_WIDTH)
+                    // 8. Size delta (right-aligned, widths.delta)
                     let prev_size = previous_state.get(&node.path).map(|p| p.size).unwrap_or(0);
                     let size_delta = info.size as i64 - prev_size as i64;
-                    let (delta_str, delta_color_name) = format_delta(size_delta, true);
+                    let (delta_str, delta_color_name) = format_delta_with(size_delta, true, size_format);
                     spans.push(Span::styled(
-                        format!("{:>width$}", delta_str, width = DELTA_WIDTH as usize),
+                        format!("{:>width$}", delta_str, width = widths.delta),
                         Style::default().fg(color_from_name(delta_color_name)),
                     ));
 
-                    // 8. LOC (right-aligned, LOC_WIDTH)
+                    // 9. LOC (right-aligned, widths.loc)
                     let loc_str = format_loc(info.loc);
                     spans.push(Span::styled(
-                        format!("{:>width$}", loc_str, width = LOC_WIDTH as usize),
-                        Style::default().fg(Color::DarkGray),
+                        format!("{:>width$}", loc_str, width = widths.loc),
+                        Style::default().fg(theme.legend_dim_color()),
                     ));
 
-                    // 9. LOC delta (right-aligned, LOC_WIDTH)
+                    // 10. LOC delta (right-aligned, widths.loc)
                     let prev_loc = previous_state.get(&node.path).map(|p| p.loc).unwrap_or(0);
                     let loc_delta = info.loc as i64 - prev_loc as i64;
-                    let (loc_delta_str, loc_delta_color_name) = format_delta(loc_delta, false);
+                    let (loc_delta_str, loc_delta_color_name) =
+                        format_delta_with(loc_delta, false, size_format);
                     spans.push(Span::styled(
-                        format!("{:>width$}", loc_delta_str, width = LOC_WIDTH as usize),
+                        format!("{:>width$}", loc_delta_str, width = widths.loc),
                         Style::default().fg(color_from_name(loc_delta_color_name)),
                     ));
+
+                    // 10.5/10.6. Opt-in `--long` Modified/Churn columns.
+                    if widths.show_details {
+                        let modified_str = format_relative_time(now, info.modified);
+                        spans.push(Span::styled(
+                            format!(" {:>width$}", modified_str, width = widths.modified),
+                            Style::default().fg(theme.legend_dim_color()),
+                        ));
+
+                        let churn_str = format_loc(loc_delta.unsigned_abs() as usize);
+                        spans.push(Span::styled(
+                            format!(" {:>width$}", churn_str, width = widths.churn),
+                            Style::default().fg(theme.legend_dim_color()),
+                        ));
+                    }
+                }
+            } else {
+                // Directories don't have their own size/delta/LOC figures --
+                // only the bar column below, scaled by their subtree size --
+                // so blank out those columns' width to keep the bar aligned
+                // under files' bars.
+                spans.push(Span::raw(" ".repeat(
+                    widths.size + widths.delta + widths.loc + widths.loc,
+                )));
+                if widths.show_details {
+                    spans.push(Span::raw(
+                        " ".repeat(1 + widths.modified + 1 + widths.churn),
+                    ));
                 }
             }
 
+            // 11. Proportional usage bar (dutree-style treemap), scaled
+            // against the largest node size in the current viewport.
+            let (bar_str, bar_color) =
+                size_bar(node_size(node, state), max_visible_size, BAR_WIDTH as usize);
+            spans.push(Span::styled(
+                format!(" {}", bar_str),
+                Style::default().fg(bar_color),
+            ));
+
             lines.push(Line::from(spans));
         }
 
         *line_index += 1;
 
-        // Recurse into children for directories.
-        if node.is_dir && !node.children.is_empty() {
+        // Recurse into children for directories, unless the user collapsed
+        // this one -- its row already drew the `▶` indicator above, and a
+        // collapsed dir simply has no visible children, not a "..." placeholder.
+        if node.is_dir && !node.children.is_empty() && !collapsed.contains(&node.path) {
             // If the entire subtree is before the viewport, skip it cheaply
             // by advancing the line counter without recursing into rendering.
-            let subtree_size =
-                count_tree_lines(&node.children, max_depth, max_files, current_depth + 1);
+            let subtree_size = count_tree_lines(
+                &node.children,
+                max_depth,
+                max_files,
+                current_depth + 1,
+                collapsed,
+            );
             if *line_index + subtree_size <= visible_start {
                 *line_index += subtree_size;
             } else {
@@ -416,6 +1071,121 @@ pub fn render_tree_lines(
                     visible_end,
                     line_index,
                     lines,
+                    max_visible_size,
+                    rainbow_edges,
+                    widths,
+                    selected,
+                    theme,
+                    collapsed,
+                    size_format,
+                    now,
+                );
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Selection
+// ---------------------------------------------------------------------------
+
+/// Companion to `render_tree_lines`: walk the same viewport traversal bounds
+/// (see `count_tree_lines`) and return, for each visible line in order, the
+/// node path it represents -- `None` for a depth/file-count truncation
+/// placeholder, which isn't a single selectable path. A caller can index
+/// into this with the current selection offset to move a focused node up or
+/// down without re-deriving the tree's line layout itself.
+pub fn visible_line_paths(
+    nodes: &[TreeNode],
+    max_depth: Option<usize>,
+    max_files: Option<usize>,
+    visible_start: usize,
+    visible_end: usize,
+    collapsed: &HashSet<PathBuf>,
+) -> Vec<Option<PathBuf>> {
+    let mut out = Vec::new();
+    let mut line_index = 0usize;
+    collect_line_paths(
+        nodes,
+        max_depth,
+        max_files,
+        0,
+        visible_start,
+        visible_end,
+        &mut line_index,
+        &mut out,
+        collapsed,
+    );
+    out
+}
+
+#[allow(clippy::too_many_arguments)]
+fn collect_line_paths(
+    nodes: &[TreeNode],
+    max_depth: Option<usize>,
+    max_files: Option<usize>,
+    current_depth: usize,
+    visible_start: usize,
+    visible_end: usize,
+    line_index: &mut usize,
+    out: &mut Vec<Option<PathBuf>>,
+    collapsed: &HashSet<PathBuf>,
+) {
+    if let Some(md) = max_depth {
+        if current_depth > md {
+            if *line_index >= visible_start && *line_index < visible_end {
+                out.push(None);
+            }
+            *line_index += 1;
+            return;
+        }
+    }
+
+    let total = nodes.len();
+    let display_count = match max_files {
+        Some(mf) => mf.min(total),
+        None => total,
+    };
+
+    for (i, node) in nodes.iter().enumerate() {
+        if *line_index >= visible_end {
+            return;
+        }
+
+        if i >= display_count {
+            if *line_index >= visible_start && *line_index < visible_end {
+                out.push(None);
+            }
+            *line_index += 1;
+            break;
+        }
+
+        if *line_index >= visible_start && *line_index < visible_end {
+            out.push(Some(node.path.clone()));
+        }
+        *line_index += 1;
+
+        if node.is_dir && !node.children.is_empty() && !collapsed.contains(&node.path) {
+            let subtree_size = count_tree_lines(
+                &node.children,
+                max_depth,
+                max_files,
+                current_depth + 1,
+                collapsed,
+            );
+            if *line_index + subtree_size <= visible_start {
+                *line_index += subtree_size;
+            } else {
+                collect_line_paths(
+                    &node.children,
+                    max_depth,
+                    max_files,
+                    current_depth + 1,
+                    visible_start,
+                    visible_end,
+                    line_index,
+                    out,
+                    collapsed,
                 );
             }
         }
@@ -424,13 +1194,14 @@ pub fn render_tree_lines(
 
 /// Count the total number of lines the tree would produce without building
 /// any `Line` objects.  This mirrors the logic of `render_tree_lines` exactly
-/// (including `max_depth` and `max_files` truncation) so the scroll indicator
-/// stays accurate.
+/// (including `max_depth`/`max_files` truncation and `collapsed` dirs) so the
+/// scroll indicator stays accurate.
 pub fn count_tree_lines(
     nodes: &[TreeNode],
     max_depth: Option<usize>,
     max_files: Option<usize>,
     current_depth: usize,
+    collapsed: &HashSet<PathBuf>,
 ) -> usize {
     if let Some(md) = max_depth {
         if current_depth > md {
@@ -454,12 +1225,124 @@ pub fn count_tree_lines(
 
         count += 1; // the node itself
 
-        if node.is_dir && !node.children.is_empty() {
-            count += count_tree_lines(&node.children, max_depth, max_files, current_depth + 1);
-        }
+        if node.is_dir && !node.children.is_empty() && !collapsed.contains(&node.path) {
+            count += count_tree_lines(
+                &node.children,
+                max_depth,
+                max_files,
+                current_depth + 1,
+                collapsed,
+            );
+        }
+    }
+
+    count
+}
+
+/// Total size of a tree node: an aggregate row's summed total, a file's
+/// size looked up from `state`, or a directory's subtree size summed
+/// recursively from its children. Backs the proportional usage bar column
+/// `render_tree_lines` draws after the LOC columns.
+fn node_size(node: &TreeNode, state: &HashMap<PathBuf, FileInfo>) -> u64 {
+    if let Some(agg) = &node.aggregated {
+        agg.size
+    } else if node.is_dir {
+        node.children.iter().map(|c| node_size(c, state)).sum()
+    } else {
+        state.get(&node.path).map(|info| info.size).unwrap_or(0)
+    }
+}
+
+/// Mirror of `render_tree_lines`'s viewport/truncation traversal (see
+/// `count_tree_lines` for the established pattern), finding the largest
+/// node size among only the rows that would actually be visible in
+/// `visible_start..visible_end`. That's the denominator the proportional
+/// usage bar column scales against.
+#[allow(clippy::too_many_arguments)]
+fn max_visible_size(
+    nodes: &[TreeNode],
+    state: &HashMap<PathBuf, FileInfo>,
+    max_depth: Option<usize>,
+    max_files: Option<usize>,
+    current_depth: usize,
+    visible_start: usize,
+    visible_end: usize,
+    line_index: &mut usize,
+    collapsed: &HashSet<PathBuf>,
+) -> u64 {
+    if let Some(md) = max_depth {
+        if current_depth > md {
+            *line_index += 1;
+            return 0;
+        }
+    }
+
+    let total = nodes.len();
+    let display_count = match max_files {
+        Some(mf) => mf.min(total),
+        None => total,
+    };
+
+    let mut max_size = 0u64;
+
+    for (i, node) in nodes.iter().enumerate() {
+        if *line_index >= visible_end {
+            return max_size;
+        }
+
+        if i >= display_count {
+            *line_index += 1;
+            break;
+        }
+
+        let visible = *line_index >= visible_start && *line_index < visible_end;
+        if visible {
+            max_size = max_size.max(node_size(node, state));
+        }
+        *line_index += 1;
+
+        if node.is_dir && !node.children.is_empty() && !collapsed.contains(&node.path) {
+            let subtree_size = count_tree_lines(
+                &node.children,
+                max_depth,
+                max_files,
+                current_depth + 1,
+                collapsed,
+            );
+            if *line_index + subtree_size <= visible_start {
+                *line_index += subtree_size;
+            } else {
+                let child_max = max_visible_size(
+                    &node.children,
+                    state,
+                    max_depth,
+                    max_files,
+                    current_depth + 1,
+                    visible_start,
+                    visible_end,
+                    line_index,
+                    collapsed,
+                );
+                max_size = max_size.max(child_max);
+            }
+        }
+    }
+
+    max_size
+}
+
+/// Render a proportional usage bar `width` cells wide: `size / max` of it
+/// filled with the same full-block character `build_sparkline` uses for
+/// its tallest bucket, colored by `get_size_color`.
+fn size_bar(size: u64, max: u64, width: usize) -> (String, Color) {
+    let filled = if max == 0 {
+        0
+    } else {
+        ((size as u128 * width as u128) / max as u128) as usize
     }
-
-    count
+    .min(width);
+    let bar = format!("{}{}", "█".repeat(filled), " ".repeat(width - filled));
+    (bar, color_from_name(get_size_color(size)))
 }
 
 // ---------------------------------------------------------------------------
@@ -467,20 +1350,26 @@ pub fn count_tree_lines(
 // ---------------------------------------------------------------------------
 
 /// Render the header area (title, watched path, recording indicator).
-fn render_header(frame: &mut Frame, area: Rect, root_path: &Path, is_recording: bool) {
+fn render_header(
+    frame: &mut Frame,
+    area: Rect,
+    root_path: &Path,
+    is_recording: bool,
+    theme: &Theme,
+) {
     let title_line = Line::from(vec![
         Span::styled(
             " ChronoCode ",
             Style::default()
                 .fg(Color::Black)
-                .bg(Color::Cyan)
+                .bg(theme.header_border_color())
                 .add_modifier(Modifier::BOLD),
         ),
         Span::raw(" "),
         Span::styled(
             "File Watcher",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.header_border_color())
                 .add_modifier(Modifier::BOLD),
         ),
     ]);
@@ -524,57 +1413,93 @@ fn render_header(frame: &mut Frame, area: Rect, root_path: &Path, is_recording:
 
 /// Render a summary line showing total files, directories, size, LOC, and
 /// change counts.
-fn render_summary_line(state: &HashMap<PathBuf, FileInfo>, changes: &ChangeSet) -> Line<'static> {
-    let mut total_files: usize = 0;
-    let mut total_dirs: usize = 0;
-    let mut total_size: u64 = 0;
-    let mut total_loc: usize = 0;
-
-    for info in state.values() {
-        if info.is_dir {
-            total_dirs += 1;
-        } else {
-            total_files += 1;
-            total_size += info.size;
-            total_loc += info.loc;
+fn render_summary_line(
+    state: &HashMap<PathBuf, FileInfo>,
+    changes: &ChangeSet,
+    theme: &Theme,
+    size_format: SizeFormat,
+) -> Line<'static> {
+    // (files, dirs, size, loc) -- same threshold-gated sequential/parallel
+    // split as `build_tree`, folded with a parallel reduce above it.
+    let (total_files, total_dirs, total_size, total_loc) = if state.len() > PARALLEL_TREE_THRESHOLD
+    {
+        state
+            .values()
+            .par_bridge()
+            .map(|info| {
+                if info.is_dir {
+                    (0usize, 1usize, 0u64, 0usize)
+                } else {
+                    (1usize, 0usize, info.size, info.loc)
+                }
+            })
+            .reduce(
+                || (0usize, 0usize, 0u64, 0usize),
+                |(af, ad, asz, aloc), (bf, bd, bsz, bloc)| (af + bf, ad + bd, asz + bsz, aloc + bloc),
+            )
+    } else {
+        let mut total_files: usize = 0;
+        let mut total_dirs: usize = 0;
+        let mut total_size: u64 = 0;
+        let mut total_loc: usize = 0;
+
+        for info in state.values() {
+            if info.is_dir {
+                total_dirs += 1;
+            } else {
+                total_files += 1;
+                total_size += info.size;
+                total_loc += info.loc;
+            }
         }
-    }
+
+        (total_files, total_dirs, total_size, total_loc)
+    };
 
     let added = changes.added.len();
     let modified = changes.modified.len();
     let deleted = changes.deleted.len();
 
     let mut spans: Vec<Span<'static>> = vec![
-        Span::styled(" Files: ", Style::default().fg(Color::DarkGray)),
+        Span::styled(" Files: ", Style::default().fg(theme.legend_dim_color())),
         Span::styled(total_files.to_string(), Style::default().fg(Color::White)),
-        Span::styled("  Dirs: ", Style::default().fg(Color::DarkGray)),
+        Span::styled("  Dirs: ", Style::default().fg(theme.legend_dim_color())),
         Span::styled(total_dirs.to_string(), Style::default().fg(Color::White)),
-        Span::styled("  Size: ", Style::default().fg(Color::DarkGray)),
-        Span::styled(format_size(total_size), Style::default().fg(Color::Cyan)),
-        Span::styled("  LOC: ", Style::default().fg(Color::DarkGray)),
-        Span::styled(format_loc(total_loc), Style::default().fg(Color::Cyan)),
+        Span::styled("  Size: ", Style::default().fg(theme.legend_dim_color())),
+        Span::styled(
+            format_size_with(total_size, size_format),
+            Style::default().fg(theme.header_border_color()),
+        ),
+        Span::styled("  LOC: ", Style::default().fg(theme.legend_dim_color())),
+        Span::styled(
+            format_loc(total_loc),
+            Style::default().fg(theme.header_border_color()),
+        ),
     ];
 
     if added > 0 || modified > 0 || deleted > 0 {
-        spans.push(Span::styled("  | ", Style::default().fg(Color::DarkGray)));
+        spans.push(Span::styled(
+            "  | ",
+            Style::default().fg(theme.legend_dim_color()),
+        ));
         if added > 0 {
             spans.push(Span::styled(
                 format!("+{}", added),
-                Style::default().fg(Color::Green),
+                Style::default().fg(theme.created_color()),
             ));
             spans.push(Span::raw(" "));
         }
         if modified > 0 {
             spans.push(Span::styled(
                 format!("~{}", modified),
-                Style::default().fg(Color::Yellow),
+                Style::default().fg(theme.modified_color()),
             ));
             spans.push(Span::raw(" "));
         }
         if deleted > 0 {
             spans.push(Span::styled(
                 format!("-{}", deleted),
-                Style::default().fg(Color::Red),
+                Style::default().fg(theme.deleted_color()),
             ));
         }
     }
@@ -592,7 +1517,11 @@ fn render_summary_line(state: &HashMap<PathBuf, FileInfo>, changes: &ChangeSet)
 /// event count in that bucket relative to the maximum across all buckets.
 /// Returns `(sparkline_string, colors)` where `colors` contains the dominant
 /// colour for each bucket character.
-fn build_sparkline(buckets: &[(usize, usize, usize)], width: usize) -> (String, Vec<Color>) {
+fn build_sparkline(
+    buckets: &[(usize, usize, usize)],
+    width: usize,
+    theme: &Theme,
+) -> (String, Vec<Color>) {
     const BLOCKS: &[char] = &[' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
 
     // Resample buckets to `width` columns if the lengths differ.
@@ -630,15 +1559,15 @@ fn build_sparkline(buckets: &[(usize, usize, usize)], width: usize) -> (String,
         };
         chars.push(BLOCKS[level]);
 
-        // Dominant colour: green for creates, yellow for modifies, red for deletes.
+        // Dominant colour: created for creates, modified for modifies, deleted for deletes.
         let color = if total == 0 {
-            Color::DarkGray
+            theme.legend_dim_color()
         } else if *c >= *m && *c >= *d {
-            Color::Green
+            theme.created_color()
         } else if *m >= *c && *m >= *d {
-            Color::Yellow
+            theme.modified_color()
         } else {
-            Color::Red
+            theme.deleted_color()
         };
         colors.push(color);
     }
@@ -647,17 +1576,17 @@ fn build_sparkline(buckets: &[(usize, usize, usize)], width: usize) -> (String,
 }
 
 /// Render the development statistics dashboard.
-fn render_stats_dashboard(frame: &mut Frame, area: Rect, stats: &Stats) {
+fn render_stats_dashboard(frame: &mut Frame, area: Rect, stats: &Stats, theme: &Theme) {
     let duration_str = StatisticsTracker::format_duration(stats.session_duration);
     let events_rate = stats.events_per_minute;
 
     // --- Activity timeline sparkline ---
     let chart_width: usize = 50;
-    let (sparkline, colors) = build_sparkline(&stats.activity_buckets, chart_width);
+    let (sparkline, colors) = build_sparkline(&stats.activity_buckets, chart_width, theme);
 
     let mut timeline_spans: Vec<Span<'static>> = vec![Span::styled(
         " Activity: ",
-        Style::default().fg(Color::DarkGray),
+        Style::default().fg(theme.legend_dim_color()),
     )];
     // Each character gets its own colour span.
     for (ch, color) in sparkline.chars().zip(colors.iter()) {
@@ -667,67 +1596,85 @@ fn render_stats_dashboard(frame: &mut Frame, area: Rect, stats: &Stats) {
     // --- Top extensions line ---
     let mut ext_spans: Vec<Span<'static>> = vec![Span::styled(
         " Top types: ",
-        Style::default().fg(Color::DarkGray),
+        Style::default().fg(theme.legend_dim_color()),
     )];
     if stats.top_extensions.is_empty() {
-        ext_spans.push(Span::styled("(none)", Style::default().fg(Color::DarkGray)));
+        ext_spans.push(Span::styled(
+            "(none)",
+            Style::default().fg(theme.legend_dim_color()),
+        ));
     } else {
         for (i, (ext, count)) in stats.top_extensions.iter().enumerate() {
             if i > 0 {
-                ext_spans.push(Span::styled(" ", Style::default().fg(Color::DarkGray)));
+                ext_spans.push(Span::styled(
+                    " ",
+                    Style::default().fg(theme.legend_dim_color()),
+                ));
             }
             ext_spans.push(Span::styled(
                 format!("{}({})", ext, count),
-                Style::default().fg(Color::Cyan),
+                Style::default().fg(theme.header_border_color()),
             ));
         }
     }
 
     let lines = vec![
         Line::from(vec![
-            Span::styled(" Session Duration: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                " Session Duration: ",
+                Style::default().fg(theme.legend_dim_color()),
+            ),
             Span::styled(duration_str, Style::default().fg(Color::White)),
-            Span::styled("    Activity Rate: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                "    Activity Rate: ",
+                Style::default().fg(theme.legend_dim_color()),
+            ),
             Span::styled(
                 format!("{} events/min", events_rate),
                 Style::default().fg(Color::White),
             ),
         ]),
         Line::from(vec![
-            Span::styled(" Created: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(" Created: ", Style::default().fg(theme.legend_dim_color())),
             Span::styled(
                 stats.total_created.to_string(),
-                Style::default().fg(Color::Green),
+                Style::default().fg(theme.created_color()),
+            ),
+            Span::styled(
+                "   Modified: ",
+                Style::default().fg(theme.legend_dim_color()),
             ),
-            Span::styled("   Modified: ", Style::default().fg(Color::DarkGray)),
             Span::styled(
                 stats.total_modified.to_string(),
-                Style::default().fg(Color::Yellow),
+                Style::default().fg(theme.modified_color()),
+            ),
+            Span::styled(
+                "   Deleted: ",
+                Style::default().fg(theme.legend_dim_color()),
             ),
-            Span::styled("   Deleted: ", Style::default().fg(Color::DarkGray)),
             Span::styled(
                 stats.total_deleted.to_string(),
-                Style::default().fg(Color::Red),
+                Style::default().fg(theme.deleted_color()),
             ),
         ]),
         Line::from(vec![
-            Span::styled(" Files: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(" Files: ", Style::default().fg(theme.legend_dim_color())),
             Span::styled(
                 stats.current_files.to_string(),
                 Style::default().fg(Color::White),
             ),
             Span::styled(
                 format!(" / {} peak", stats.peak_files),
-                Style::default().fg(Color::DarkGray),
+                Style::default().fg(theme.legend_dim_color()),
             ),
-            Span::styled("    Dirs: ", Style::default().fg(Color::DarkGray)),
+            Span::styled("    Dirs: ", Style::default().fg(theme.legend_dim_color())),
             Span::styled(
                 stats.current_dirs.to_string(),
                 Style::default().fg(Color::White),
             ),
             Span::styled(
                 format!(" / {} peak", stats.peak_dirs),
-                Style::default().fg(Color::DarkGray),
+                Style::default().fg(theme.legend_dim_color()),
             ),
         ]),
         Line::from(timeline_spans),
@@ -737,11 +1684,11 @@ fn render_stats_dashboard(frame: &mut Frame, area: Rect, stats: &Stats) {
     let text = Text::from(lines);
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan))
+        .border_style(Style::default().fg(theme.header_border_color()))
         .title(Span::styled(
             " DEVELOPMENT STATISTICS ",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.header_border_color())
                 .add_modifier(Modifier::BOLD),
         ));
 
@@ -749,6 +1696,60 @@ fn render_stats_dashboard(frame: &mut Frame, area: Rect, stats: &Stats) {
     frame.render_widget(paragraph, area);
 }
 
+// ---------------------------------------------------------------------------
+// Diagnostics log panel
+// ---------------------------------------------------------------------------
+
+/// Color a log level the way the rest of the UI colors severity (red for
+/// errors/warnings, matching the watcher-error legend line).
+fn level_color(level: tracing::Level) -> Color {
+    match level {
+        tracing::Level::ERROR => Color::Red,
+        tracing::Level::WARN => Color::Yellow,
+        tracing::Level::INFO => Color::Cyan,
+        tracing::Level::DEBUG | tracing::Level::TRACE => Color::DarkGray,
+    }
+}
+
+/// Render the scrollable diagnostics log panel, toggled with `l`. Shows the
+/// most recent lines that fit, newest at the bottom.
+fn render_log_panel(frame: &mut Frame, area: Rect, logs: &[LogEntry], theme: &Theme) {
+    let visible_rows = area.height.saturating_sub(2) as usize; // minus borders
+    let start = logs.len().saturating_sub(visible_rows);
+
+    let lines: Vec<Line> = logs[start..]
+        .iter()
+        .map(|entry| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{} ", entry.time),
+                    Style::default().fg(theme.legend_dim_color()),
+                ),
+                Span::styled(
+                    format!("{:<5} ", entry.level),
+                    Style::default()
+                        .fg(level_color(entry.level))
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(entry.message.clone(), Style::default().fg(Color::White)),
+            ])
+        })
+        .collect();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.log_border_color()))
+        .title(Span::styled(
+            " DIAGNOSTICS (l to hide) ",
+            Style::default()
+                .fg(theme.log_border_color())
+                .add_modifier(Modifier::BOLD),
+        ));
+
+    let paragraph = Paragraph::new(Text::from(lines)).block(block);
+    frame.render_widget(paragraph, area);
+}
+
 // ---------------------------------------------------------------------------
 // Legend
 // ---------------------------------------------------------------------------
@@ -767,15 +1768,44 @@ fn render_legend(
     total_lines: u16,
     viewport_height: u16,
     last_error: Option<&str>,
+    recording_paused: bool,
+    bookmark_active: bool,
+    bookmark_name: &str,
+    theme: &Theme,
 ) {
-    let mut spans: Vec<Span<'static>> = if search_active {
+    let mut spans: Vec<Span<'static>> = if bookmark_active {
+        // Bookmark-naming input mode: show the bookmark bar with cursor.
+        vec![
+            Span::styled(
+                " bookmark ",
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(theme.modified_color())
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!(" {}", bookmark_name),
+                Style::default().fg(Color::White),
+            ),
+            Span::styled(
+                "_",
+                Style::default()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::SLOW_BLINK),
+            ),
+            Span::styled(
+                "  (Enter to save, Esc to cancel)",
+                Style::default().fg(theme.legend_dim_color()),
+            ),
+        ]
+    } else if search_active {
         // Search input mode: show the search bar with cursor.
         vec![
             Span::styled(
                 " / ",
                 Style::default()
                     .fg(Color::Black)
-                    .bg(Color::Cyan)
+                    .bg(theme.header_border_color())
                     .add_modifier(Modifier::BOLD),
             ),
             Span::styled(
@@ -788,57 +1818,73 @@ fn render_legend(
                     .fg(Color::White)
                     .add_modifier(Modifier::SLOW_BLINK),
             ),
-            Span::styled("  (Esc to cancel)", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                "  (Esc to cancel)",
+                Style::default().fg(theme.legend_dim_color()),
+            ),
         ]
     } else if !search_query.is_empty() {
         // Filter is active but not in input mode.
         vec![
-            Span::styled(" Filter: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(" Filter: ", Style::default().fg(theme.legend_dim_color())),
             Span::styled(
                 format!("\"{}\"", search_query),
                 Style::default()
-                    .fg(Color::Cyan)
+                    .fg(theme.header_border_color())
                     .add_modifier(Modifier::BOLD),
             ),
             Span::styled(
                 "  (Esc to clear, / to edit)  |  j/k scroll",
-                Style::default().fg(Color::DarkGray),
+                Style::default().fg(theme.legend_dim_color()),
             ),
         ]
     } else {
         // Normal legend.
         vec![
-            Span::styled(" Legend: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(" Legend: ", Style::default().fg(theme.legend_dim_color())),
             Span::styled(
                 "NEW",
                 Style::default()
-                    .fg(Color::Green)
+                    .fg(theme.created_color())
                     .add_modifier(Modifier::BOLD),
             ),
             Span::raw("  "),
             Span::styled(
                 "MODIFIED",
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.modified_color())
                     .add_modifier(Modifier::BOLD),
             ),
             Span::raw("  "),
             Span::styled(
                 "DELETED",
-                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                Style::default()
+                    .fg(theme.deleted_color())
+                    .add_modifier(Modifier::BOLD),
             ),
             Span::styled(
-                "  |  q quit  / search  j/k scroll  g/G top/bottom",
-                Style::default().fg(Color::DarkGray),
+                "  |  q quit  / search  l logs  p pause  b bookmark  j/k scroll  g/G top/bottom  s sort  S reverse  D details",
+                Style::default().fg(theme.legend_dim_color()),
             ),
         ]
     };
 
+    // Show the recording-paused indicator.
+    if recording_paused {
+        spans.push(Span::styled(
+            "  [PAUSED]",
+            Style::default()
+                .fg(Color::Black)
+                .bg(theme.modified_color())
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
     // Show watcher error if present.
     if let Some(err) = last_error {
         spans.push(Span::styled(
             format!("  [!] {}", err),
-            Style::default().fg(Color::Red),
+            Style::default().fg(theme.deleted_color()),
         ));
     }
 
@@ -848,7 +1894,7 @@ fn render_legend(
         let current_bottom = (scroll_offset + viewport_height).min(total_lines);
         spans.push(Span::styled(
             format!("  [{}-{}/{}]", current_top, current_bottom, total_lines),
-            Style::default().fg(Color::Cyan),
+            Style::default().fg(theme.scroll_indicator_color()),
         ));
     }
 
@@ -861,38 +1907,91 @@ fn render_legend(
 // Column headers for the tree view
 // ---------------------------------------------------------------------------
 
-/// Return a `Line` with column headers (Name, Status, Size, Delta, LOC, LOC+/-).
-fn tree_column_headers() -> Line<'static> {
+/// `▲`/`▼` next to a header's label when `sort` is the mode that column
+/// drives, empty otherwise. `Recent` never marks a column, not even the
+/// `--long` Modified one.
+fn sort_arrow(sort: SortKind, reverse: bool, column: SortKind) -> &'static str {
+    if sort != column {
+        return "";
+    }
+    if reverse {
+        " \u{25b2}"
+    } else {
+        " \u{25bc}"
+    }
+}
+
+/// Return a `Line` with column headers (Name, Status, Size, Delta, LOC,
+/// LOC+/-, and -- if `widths.show_details` -- Modified/Churn), padded to
+/// `widths` -- the same content-measured widths `render_tree_lines` used for
+/// this render (see `compute_column_widths`). The header driving the active
+/// `sort` mode (see `SortKind`) is marked with a `▲`/`▼` arrow, `s`/`S` key
+/// style.
+fn tree_column_headers(widths: &ColumnWidths, sort: SortKind, sort_reverse: bool, theme: &Theme) -> Line<'static> {
     let hdr_style = Style::default()
-        .fg(Color::DarkGray)
+        .fg(theme.legend_dim_color())
         .add_modifier(Modifier::UNDERLINED);
 
-    Line::from(vec![
+    let mut spans = vec![
         Span::styled(
-            format!(" {:<width$}", "Name", width = NAME_WIDTH as usize),
+            format!(
+                " {:<width$}",
+                format!("Name{}", sort_arrow(sort, sort_reverse, SortKind::Name)),
+                width = widths.name
+            ),
             hdr_style,
         ),
         Span::styled(
-            format!("{:<width$}", "Status", width = STATUS_WIDTH as usize),
+            format!(
+                "{:<width$}",
+                format!("Status{}", sort_arrow(sort, sort_reverse, SortKind::Churn)),
+                width = STATUS_WIDTH as usize
+            ),
             hdr_style,
         ),
         Span::styled(
-            format!("{:>width$}", "Size", width = SIZE_WIDTH as usize),
+            format!(
+                "{:>width$}",
+                format!("Size{}", sort_arrow(sort, sort_reverse, SortKind::Size)),
+                width = widths.size
+            ),
             hdr_style,
         ),
         Span::styled(
-            format!("{:>width$}", "Delta", width = DELTA_WIDTH as usize),
+            format!("{:>width$}", "Delta", width = widths.delta),
             hdr_style,
         ),
         Span::styled(
-            format!("{:>width$}", "LOC", width = LOC_WIDTH as usize),
+            format!(
+                "{:>width$}",
+                format!("LOC{}", sort_arrow(sort, sort_reverse, SortKind::Loc)),
+                width = widths.loc
+            ),
             hdr_style,
         ),
         Span::styled(
-            format!("{:>width$}", "LOC+/-", width = LOC_WIDTH as usize),
+            format!("{:>width$}", "LOC+/-", width = widths.loc),
             hdr_style,
         ),
-    ])
+    ];
+
+    if widths.show_details {
+        spans.push(Span::styled(
+            format!(" {:>width$}", "Modified", width = widths.modified),
+            hdr_style,
+        ));
+        spans.push(Span::styled(
+            format!(" {:>width$}", "Churn", width = widths.churn),
+            hdr_style,
+        ));
+    }
+
+    spans.push(Span::styled(
+        format!(" {:<width$}", "Usage", width = BAR_WIDTH as usize),
+        hdr_style,
+    ));
+
+    Line::from(spans)
 }
 
 // ---------------------------------------------------------------------------
@@ -917,12 +2016,26 @@ pub fn render_ui(
     search_query: &str,
     search_active: bool,
     last_error: Option<&str>,
+    logs: Option<&[LogEntry]>,
+    recording_paused: bool,
+    bookmark_active: bool,
+    bookmark_name: &str,
+    aggregate_threshold: Option<u64>,
+    rainbow_edges: bool,
+    selected: Option<&Path>,
+    sort: SortKind,
+    sort_reverse: bool,
+    theme: &Theme,
+    collapsed: &HashSet<PathBuf>,
+    size_format: SizeFormat,
+    show_details: bool,
 ) -> u16 {
     let size = frame.area();
 
     // ----- Determine layout constraints -----
 
     let stats_height: u16 = if show_stats && stats.is_some() { 9 } else { 0 };
+    let log_height: u16 = if logs.is_some() { 9 } else { 0 };
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -931,6 +2044,7 @@ pub fn render_ui(
             Constraint::Length(1),            // summary line
             Constraint::Min(1),               // tree area
             Constraint::Length(stats_height), // stats dashboard
+            Constraint::Length(log_height),   // diagnostics log panel
             Constraint::Length(1),            // legend
         ])
         .split(size);
@@ -939,17 +2053,18 @@ pub fn render_ui(
     let summary_area = chunks[1];
     let tree_area = chunks[2];
     let stats_area = chunks[3];
-    let legend_area = chunks[4];
+    let log_area = chunks[4];
+    let legend_area = chunks[5];
 
     // ----- Header -----
-    render_header(frame, header_area, root_path, is_recording);
+    render_header(frame, header_area, root_path, is_recording, theme);
 
     // ----- Summary line -----
-    let summary_line = render_summary_line(state, changes);
+    let summary_line = render_summary_line(state, changes, theme, size_format);
     frame.render_widget(Paragraph::new(summary_line), summary_area);
 
     // ----- Tree -----
-    let tree_nodes = build_tree(root_path, state);
+    let tree_nodes = build_tree(root_path, state, aggregate_threshold, sort, sort_reverse, changes);
     let tree_nodes = if search_query.is_empty() {
         tree_nodes
     } else {
@@ -958,7 +2073,7 @@ pub fn render_ui(
 
     // Count total lines cheaply (no Line/Span allocations) for the scroll
     // indicator.  +1 for the column header row.
-    let content_lines = count_tree_lines(&tree_nodes, max_depth, max_files, 0);
+    let content_lines = count_tree_lines(&tree_nodes, max_depth, max_files, 0, collapsed);
     let total_tree_lines = (content_lines + 1) as u16; // +1 for column headers
 
     // Virtual scrolling: only build Line objects for the visible viewport.
@@ -967,11 +2082,6 @@ pub fn render_ui(
 
     let mut tree_lines: Vec<Line<'static>> = Vec::with_capacity(viewport_height);
 
-    // The column header is always line 0.
-    if scroll == 0 {
-        tree_lines.push(tree_column_headers());
-    }
-
     // Content lines start at global index 1 (after the header).
     // Determine the visible window within content lines.
     let content_visible_start = if scroll == 0 {
@@ -979,9 +2089,56 @@ pub fn render_ui(
     } else {
         scroll.saturating_sub(1)
     };
-    let remaining_viewport = viewport_height.saturating_sub(tree_lines.len());
+    let remaining_viewport = viewport_height.saturating_sub(if scroll == 0 { 1 } else { 0 });
     let content_visible_end = content_visible_start + remaining_viewport;
 
+    // One "now" for the whole render, so the Modified column's relative
+    // timestamps (and the width measured for them) never disagree within a
+    // single frame.
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0);
+
+    // Column widths measured from the rows actually visible in this
+    // viewport, exa `details.rs`-style, replacing the old fixed-width
+    // constants -- see `compute_column_widths`.
+    let widths = compute_column_widths(
+        &tree_nodes,
+        state,
+        previous_state,
+        max_depth,
+        max_files,
+        content_visible_start,
+        content_visible_end,
+        tree_area.width,
+        collapsed,
+        size_format,
+        show_details,
+        now,
+    );
+
+    // The column header is always line 0.
+    if scroll == 0 {
+        tree_lines.push(tree_column_headers(&widths, sort, sort_reverse, theme));
+    }
+
+    // Largest node size among only the rows actually visible in this
+    // viewport -- the denominator for the proportional usage bar column.
+    let mut max_size_line_index: usize = 0;
+    let viewport_max_size = max_visible_size(
+        &tree_nodes,
+        state,
+        max_depth,
+        max_files,
+        0,
+        content_visible_start,
+        content_visible_end,
+        &mut max_size_line_index,
+        collapsed,
+    )
+    .max(1);
+
     let mut line_index: usize = 0;
     render_tree_lines(
         &tree_nodes,
@@ -996,6 +2153,14 @@ pub fn render_ui(
         content_visible_end,
         &mut line_index,
         &mut tree_lines,
+        viewport_max_size,
+        rainbow_edges,
+        &widths,
+        selected,
+        theme,
+        collapsed,
+        size_format,
+        now,
     );
 
     let tree_text = Text::from(tree_lines);
@@ -1006,10 +2171,15 @@ pub fn render_ui(
     // ----- Stats dashboard -----
     if show_stats {
         if let Some(s) = stats {
-            render_stats_dashboard(frame, stats_area, s);
+            render_stats_dashboard(frame, stats_area, s, theme);
         }
     }
 
+    // ----- Diagnostics log panel -----
+    if let Some(entries) = logs {
+        render_log_panel(frame, log_area, entries, theme);
+    }
+
     // ----- Legend -----
     render_legend(
         frame,
@@ -1020,6 +2190,10 @@ pub fn render_ui(
         total_tree_lines,
         tree_area.height,
         last_error,
+        recording_paused,
+        bookmark_active,
+        bookmark_name,
+        theme,
     );
 
     total_tree_lines
@@ -1033,24 +2207,11 @@ pub fn render_ui(
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_color_from_name() {
-        assert_eq!(color_from_name("dim"), Color::DarkGray);
-        assert_eq!(color_from_name("cyan"), Color::Cyan);
-        assert_eq!(color_from_name("yellow"), Color::Yellow);
-        assert_eq!(color_from_name("red"), Color::Red);
-        assert_eq!(color_from_name("green"), Color::Green);
-        assert_eq!(color_from_name("blue"), Color::Blue);
-        assert_eq!(color_from_name("magenta"), Color::Magenta);
-        assert_eq!(color_from_name("white"), Color::White);
-        assert_eq!(color_from_name("unknown"), Color::Reset);
-    }
-
     #[test]
     fn test_build_tree_empty() {
         let state: HashMap<PathBuf, FileInfo> = HashMap::new();
         let root = PathBuf::from("/tmp/test");
-        let tree = build_tree(&root, &state);
+        let tree = build_tree(&root, &state, None, SortKind::Name, false, &ChangeSet::default());
         assert!(tree.is_empty());
     }
 
@@ -1087,7 +2248,7 @@ mod tests {
             },
         );
 
-        let tree = build_tree(&root, &state);
+        let tree = build_tree(&root, &state, None, SortKind::Name, false, &ChangeSet::default());
 
         // Directory should come first.
         assert_eq!(tree.len(), 3);
@@ -1098,6 +2259,103 @@ mod tests {
         assert_eq!(tree[2].name, "zebra.txt");
     }
 
+    #[test]
+    fn test_build_tree_sort_by_size_descending() {
+        let root = PathBuf::from("/project");
+        let mut state = HashMap::new();
+
+        state.insert(
+            PathBuf::from("/project/small.txt"),
+            FileInfo {
+                size: 10,
+                modified: 0.0,
+                is_dir: false,
+                loc: 1,
+                ..Default::default()
+            },
+        );
+        state.insert(
+            PathBuf::from("/project/big.txt"),
+            FileInfo {
+                size: 10_000,
+                modified: 0.0,
+                is_dir: false,
+                loc: 1,
+                ..Default::default()
+            },
+        );
+
+        let tree = build_tree(
+            &root,
+            &state,
+            None,
+            SortKind::Size,
+            false,
+            &ChangeSet::default(),
+        );
+
+        assert_eq!(tree[0].name, "big.txt");
+        assert_eq!(tree[1].name, "small.txt");
+
+        // Reversed flips it back to smallest-first.
+        let tree_rev = build_tree(
+            &root,
+            &state,
+            None,
+            SortKind::Size,
+            true,
+            &ChangeSet::default(),
+        );
+        assert_eq!(tree_rev[0].name, "small.txt");
+        assert_eq!(tree_rev[1].name, "big.txt");
+    }
+
+    #[test]
+    fn test_build_tree_parallel_path_matches_sequential() {
+        // Past `PARALLEL_TREE_THRESHOLD`, `build_tree` switches to rayon's
+        // `par_iter`; the result should be bit-for-bit identical to the
+        // sequential path since `build_from_map` sorts after collecting.
+        let root = PathBuf::from("/project");
+        let mut state = HashMap::new();
+
+        for i in 0..(PARALLEL_TREE_THRESHOLD + 1) {
+            state.insert(
+                PathBuf::from(format!("/project/file_{i:05}.txt")),
+                FileInfo {
+                    size: i as u64,
+                    modified: 0.0,
+                    is_dir: false,
+                    loc: 1,
+                    ..Default::default()
+                },
+            );
+        }
+
+        let tree = build_tree(&root, &state, None, SortKind::Name, false, &ChangeSet::default());
+
+        assert_eq!(tree.len(), state.len());
+        // Name sort stays alphabetical regardless of which path built it.
+        assert!(tree.windows(2).all(|w| w[0].name <= w[1].name));
+
+        // The same map built on the sequential path gives the same order.
+        let mut children_map: HashMap<PathBuf, Vec<(PathBuf, bool)>> = HashMap::new();
+        for (path, info) in &state {
+            if let Some(parent) = path.parent() {
+                children_map
+                    .entry(parent.to_path_buf())
+                    .or_default()
+                    .push((path.clone(), info.is_dir));
+            }
+        }
+        let sequential = build_from_map(
+            &root, &children_map, &state, None, SortKind::Name, false,
+            &ChangeSet::default(), false,
+        );
+        let names: Vec<&str> = tree.iter().map(|n| n.name.as_str()).collect();
+        let sequential_names: Vec<&str> = sequential.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, sequential_names);
+    }
+
     #[test]
     fn test_render_tree_lines_basic() {
         let root = PathBuf::from("/project");
@@ -1114,10 +2372,15 @@ mod tests {
 
         let changes = ChangeSet::default();
         let previous_state = HashMap::new();
-        let nodes = build_tree(&root, &state);
+        let nodes = build_tree(&root, &state, None, SortKind::Name, false, &ChangeSet::default());
         let mut lines: Vec<Line<'static>> = Vec::new();
         let mut line_index = 0;
 
+        let collapsed = HashSet::new();
+        let widths = compute_column_widths(
+            &nodes, &state, &previous_state, None, None, 0, usize::MAX, 80, &collapsed,
+            SizeFormat::default(), false, 0.0,
+        );
         render_tree_lines(
             &nodes,
             " ",
@@ -1131,6 +2394,14 @@ mod tests {
             usize::MAX,
             &mut line_index,
             &mut lines,
+            512,
+            false,
+            &widths,
+            None,
+            &Theme::default(),
+            &collapsed,
+            SizeFormat::default(),
+            0.0,
         );
 
         assert_eq!(lines.len(), 1);
@@ -1162,12 +2433,17 @@ mod tests {
 
         let changes = ChangeSet::default();
         let previous_state = HashMap::new();
-        let nodes = build_tree(&root, &state);
+        let nodes = build_tree(&root, &state, None, SortKind::Name, false, &ChangeSet::default());
         let mut lines: Vec<Line<'static>> = Vec::new();
         let mut line_index = 0;
 
         // max_depth = 0 means only the root-level children, no recursion into
         // subdirectories.
+        let collapsed = HashSet::new();
+        let widths = compute_column_widths(
+            &nodes, &state, &previous_state, Some(0), None, 0, usize::MAX, 80, &collapsed,
+            SizeFormat::default(), false, 0.0,
+        );
         render_tree_lines(
             &nodes,
             " ",
@@ -1181,10 +2457,154 @@ mod tests {
             usize::MAX,
             &mut line_index,
             &mut lines,
+            1024,
+            false,
+            &widths,
+            None,
+            &Theme::default(),
+            &collapsed,
+            SizeFormat::default(),
+            0.0,
         );
 
         // Should have the "src" dir line, but its children should be replaced
         // by a "..." placeholder.
         assert!(lines.len() >= 1);
     }
+
+    #[test]
+    fn test_visible_line_paths_matches_nodes() {
+        let root = PathBuf::from("/project");
+        let mut state = HashMap::new();
+        state.insert(
+            PathBuf::from("/project/a.rs"),
+            FileInfo {
+                size: 10,
+                modified: 0.0,
+                is_dir: false,
+                loc: 5,
+                ..Default::default()
+            },
+        );
+        state.insert(
+            PathBuf::from("/project/b.rs"),
+            FileInfo {
+                size: 20,
+                modified: 0.0,
+                is_dir: false,
+                loc: 5,
+                ..Default::default()
+            },
+        );
+
+        let nodes = build_tree(&root, &state, None, SortKind::Name, false, &ChangeSet::default());
+        let paths = visible_line_paths(&nodes, None, None, 0, usize::MAX, &HashSet::new());
+
+        assert_eq!(
+            paths,
+            vec![
+                Some(PathBuf::from("/project/a.rs")),
+                Some(PathBuf::from("/project/b.rs")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compute_column_widths_measures_widest_cell() {
+        let root = PathBuf::from("/project");
+        let mut state = HashMap::new();
+        state.insert(
+            PathBuf::from("/project/a.rs"),
+            FileInfo {
+                size: 10,
+                modified: 0.0,
+                is_dir: false,
+                loc: 5,
+                ..Default::default()
+            },
+        );
+        state.insert(
+            PathBuf::from("/project/a_much_longer_filename.rs"),
+            FileInfo {
+                size: 2_097_152,
+                modified: 0.0,
+                is_dir: false,
+                loc: 5000,
+                ..Default::default()
+            },
+        );
+
+        let previous_state = HashMap::new();
+        let nodes = build_tree(&root, &state, None, SortKind::Name, false, &ChangeSet::default());
+        let widths = compute_column_widths(
+            &nodes, &state, &previous_state, None, None, 0, usize::MAX, 200, &HashSet::new(),
+            SizeFormat::default(), false, 0.0,
+        );
+
+        // Wide enough to clear the long name plus its prefix/connector/emoji.
+        assert!(widths.name > "Name".len());
+        // "2.0 MB" is wider than the "Size" header.
+        assert!(widths.size >= "2.0 MB".len());
+    }
+
+    #[test]
+    fn test_compute_column_widths_clamps_name_to_area() {
+        let root = PathBuf::from("/project");
+        let mut state = HashMap::new();
+        state.insert(
+            PathBuf::from("/project/a_very_long_filename_indeed.rs"),
+            FileInfo {
+                size: 10,
+                modified: 0.0,
+                is_dir: false,
+                loc: 5,
+                ..Default::default()
+            },
+        );
+
+        let previous_state = HashMap::new();
+        let nodes = build_tree(&root, &state, None, SortKind::Name, false, &ChangeSet::default());
+        // A terminal narrower than the fixed columns clamps the name column
+        // down to the floor (the row itself is still wider than `area_width`).
+        let widths = compute_column_widths(
+            &nodes, &state, &previous_state, None, None, 0, usize::MAX, 20, &HashSet::new(),
+            SizeFormat::default(), false, 0.0,
+        );
+
+        assert_eq!(widths.name, MIN_NAME_WIDTH);
+    }
+
+    #[test]
+    fn test_compute_column_widths_details_collapse_when_narrow() {
+        let root = PathBuf::from("/project");
+        let mut state = HashMap::new();
+        state.insert(
+            PathBuf::from("/project/main.rs"),
+            FileInfo {
+                size: 10,
+                modified: 0.0,
+                is_dir: false,
+                loc: 5,
+                ..Default::default()
+            },
+        );
+
+        let previous_state = HashMap::new();
+        let nodes = build_tree(&root, &state, None, SortKind::Name, false, &ChangeSet::default());
+
+        // Plenty of room: the opt-in Modified/Churn columns fit.
+        let wide = compute_column_widths(
+            &nodes, &state, &previous_state, None, None, 0, usize::MAX, 200, &HashSet::new(),
+            SizeFormat::default(), true, 0.0,
+        );
+        assert!(wide.show_details);
+
+        // Too narrow for Name plus the detail columns: silently collapse
+        // back to the compact layout even though details were requested.
+        let narrow = compute_column_widths(
+            &nodes, &state, &previous_state, None, None, 0, usize::MAX, 20, &HashSet::new(),
+            SizeFormat::default(), true, 0.0,
+        );
+        assert!(!narrow.show_details);
+    }
 }
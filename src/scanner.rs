@@ -1,24 +1,48 @@
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::sync::mpsc;
-use std::time::UNIX_EPOCH;
+use std::sync::{mpsc, Arc};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use walkdir::WalkDir;
 
+use crate::git::GitStatus;
 use crate::gitignore::GitignoreParser;
 use crate::recording::EventLogger;
-use crate::state::{get_loc, ChangeSet, EventType, FileInfo};
-use crate::statistics::StatisticsTracker;
+use crate::state::{get_loc, ChangeSet, EventType, FileEvent, FileInfo};
+use crate::statistics::{SessionLog, StatisticsTracker};
 
 /// Cached LOC entry: `(mtime, size, loc)`.
 type LocCacheEntry = (f64, u64, usize);
 
+/// Number of entries to accumulate before flushing an intermediate
+/// [`ScanResult`] from a background scan. Borrowed from Zed's
+/// `BackgroundScanner`, which recomputes git status in bounded batches and
+/// yields in between so other work on the thread stays responsive -- on a
+/// huge tree, waiting for the whole walk to finish before the UI sees
+/// anything makes the scan look hung.
+const SCAN_BATCH_SIZE: usize = 2000;
+
+/// Default number of worker threads used to count LOC in parallel once the
+/// initial scan has completed. Kept small and fixed rather than
+/// `std::thread::available_parallelism()` so a big scan doesn't compete with
+/// everything else on the machine; override with `CHRONOCODE_LOC_WORKERS`.
+const DEFAULT_LOC_POOL_SIZE: usize = 4;
+
+/// Number of files handed to a single LOC-counting job at a time. Chunking
+/// bounds how long a worker holds its current job before checking for more
+/// work, rather than claiming the whole backlog up front.
+const LOC_CHUNK_SIZE: usize = 64;
+
 /// Result of a background scan, returned via channel.
 pub struct ScanResult {
     pub state: HashMap<PathBuf, FileInfo>,
     /// The gitignore parser, returned so it can be put back into the tracker
     /// (it may have been updated with newly-discovered nested .gitignore files).
     pub gitignore_parser: Option<GitignoreParser>,
+    /// `true` for an intermediate batch sent mid-walk (`state` holds only
+    /// the entries found since the last batch); `false` for the final
+    /// batch, where `state` holds the complete, authoritative tree.
+    pub partial: bool,
 }
 
 /// Tracks directory state across scans and detects file-level changes.
@@ -32,6 +56,9 @@ pub struct ChangeTracker {
     gitignore_parser: Option<GitignoreParser>,
     pub event_logger: Option<EventLogger>,
     pub stats_tracker: Option<StatisticsTracker>,
+    /// Append-only JSONL log of every event, independent of `event_logger`'s
+    /// recording file (see `--stats-log`).
+    pub session_log: Option<SessionLog>,
     /// Cache of LOC counts keyed by path.  Only recount when mtime or size
     /// changes compared to the cached values.
     loc_cache: HashMap<PathBuf, LocCacheEntry>,
@@ -46,29 +73,67 @@ pub struct ChangeTracker {
     /// Whether the initial scan has completed.  LOC counting is deferred
     /// until after the first scan to avoid reading every file at startup.
     initial_scan_done: bool,
+    /// Most recently applied per-path git status, keyed by absolute path.
+    /// Refreshed off the main thread (see `git::read_git_status`) and merged
+    /// in via [`apply_git_status`](Self::apply_git_status);
+    /// `scan_directory_impl` reads from this snapshot to populate each
+    /// `FileInfo::git_status` at merge time rather than shelling out per
+    /// file.
+    git_status: HashMap<PathBuf, GitStatus>,
+    /// Last-seen branch/commit, used to classify a change under the shallow-
+    /// scanned `.git/HEAD`/`.git/refs` paths as a [`EventType::Commit`],
+    /// [`EventType::BranchChanged`], or [`EventType::Checkout`]. `None`
+    /// until the first scan has had a chance to seed it, so startup never
+    /// emits a spurious event for the repo's pre-existing state.
+    git_head_baseline: Option<crate::git::GitState>,
     /// Monotonically increasing counter bumped whenever `current_state`
     /// changes.  Used by the render cache to detect when it needs to rebuild.
     pub state_generation: u64,
+    /// While `true`, `update` keeps diffing and refreshing `current_state`
+    /// as normal but stops forwarding changes to `event_logger` -- so the UI
+    /// and stats keep moving but nothing new lands in the recording. Toggled
+    /// by the `p` keybinding.
+    pub recording_paused: bool,
 }
 
 impl ChangeTracker {
     /// Create a new `ChangeTracker` for the given root directory.
     ///
     /// If `use_gitignore` is `true`, a [`GitignoreParser`] is created and used
-    /// to skip ignored paths during scans.
+    /// to skip ignored paths during scans. `use_default_ignores` and
+    /// `extra_ignore_globs` are forwarded to
+    /// [`GitignoreParser::with_options`] and have no effect when
+    /// `use_gitignore` is `false`.
     pub fn new(
         root_path: PathBuf,
         use_gitignore: bool,
+        use_default_ignores: bool,
+        extra_ignore_globs: Vec<String>,
         show_hidden: bool,
-        event_logger: Option<EventLogger>,
+        mut event_logger: Option<EventLogger>,
         stats_tracker: Option<StatisticsTracker>,
+        session_log: Option<SessionLog>,
     ) -> Self {
         let gitignore_parser = if use_gitignore {
-            Some(GitignoreParser::new(&root_path))
+            Some(GitignoreParser::with_options(
+                &root_path,
+                crate::gitignore::DEFAULT_IGNORE_FILENAMES
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect(),
+                use_default_ignores,
+                extra_ignore_globs,
+            ))
         } else {
             None
         };
 
+        // The logger is built before the parser exists (see `App::new`), so
+        // hand it its first snapshot here rather than at construction time.
+        if let Some(logger) = event_logger.as_mut() {
+            logger.set_gitignore(gitignore_parser.clone().map(Arc::new));
+        }
+
         Self {
             previous_state: HashMap::new(),
             current_state: HashMap::new(),
@@ -79,11 +144,15 @@ impl ChangeTracker {
             gitignore_parser,
             event_logger,
             stats_tracker,
+            session_log,
             loc_cache: HashMap::new(),
             worktree_paths: Vec::new(),
             worktree_path_set: HashSet::new(),
             initial_scan_done: false,
+            git_status: HashMap::new(),
+            git_head_baseline: None,
             state_generation: 0,
+            recording_paused: false,
         }
     }
 
@@ -114,6 +183,7 @@ impl ChangeTracker {
         let worktree_path_set = self.worktree_path_set.clone();
         let initial_scan_done = self.initial_scan_done;
         let gitignore_parser = self.gitignore_parser.take();
+        let git_status = self.git_status.clone();
 
         std::thread::spawn(move || {
             // The background scan uses its own empty LOC cache.  On the
@@ -129,10 +199,13 @@ impl ChangeTracker {
                 initial_scan_done,
                 &mut loc_cache,
                 gitignore_parser,
+                Some(&tx),
+                &git_status,
             );
             let _ = tx.send(ScanResult {
                 state,
                 gitignore_parser,
+                partial: false,
             });
         });
 
@@ -140,8 +213,26 @@ impl ChangeTracker {
     }
 
     /// Apply the result of a background scan to the tracker state.
+    ///
+    /// A partial batch is merged into `current_state` in place; only the
+    /// first one rotates `previous_state` and bumps `state_generation`, so
+    /// later batches extend `current_state` without a further generation
+    /// bump and anything reading it sees the tree fill in. The final (non-partial) batch
+    /// carries the complete, authoritative state, so it replaces
+    /// `current_state` wholesale; deletions can only be computed from that
+    /// point on, since every earlier batch saw an incomplete key set.
     pub fn apply_scan_result(&mut self, result: ScanResult) {
+        if result.partial {
+            if self.current_state.is_empty() {
+                self.previous_state = std::mem::take(&mut self.current_state);
+                self.state_generation += 1;
+            }
+            self.current_state.extend(result.state);
+            return;
+        }
+
         self.gitignore_parser = result.gitignore_parser;
+        self.sync_event_logger_gitignore();
         self.previous_state = std::mem::take(&mut self.current_state);
         self.current_state = result.state;
         self.state_generation += 1;
@@ -163,11 +254,26 @@ impl ChangeTracker {
             self.initial_scan_done,
             &mut self.loc_cache,
             gitignore_parser,
+            None,
+            &self.git_status,
         );
         self.gitignore_parser = parser_out;
+        self.sync_event_logger_gitignore();
         state
     }
 
+    /// Hand the attached [`EventLogger`] a fresh snapshot of `gitignore_parser`.
+    ///
+    /// Called after every full scan, since the walk may have loaded nested
+    /// `.gitignore` files that change a path's ignored status -- the logger
+    /// must check against the parser's current rules at event time, not a
+    /// decision cached from before those rules existed.
+    fn sync_event_logger_gitignore(&mut self) {
+        if let Some(logger) = self.event_logger.as_mut() {
+            logger.set_gitignore(self.gitignore_parser.clone().map(Arc::new));
+        }
+    }
+
     // ------------------------------------------------------------------
     // State update & change detection
     // ------------------------------------------------------------------
@@ -182,8 +288,27 @@ impl ChangeTracker {
         self.previous_state = std::mem::take(&mut self.current_state);
         self.current_state = self.scan_directory(root_path);
         self.state_generation += 1;
+        self.diff_and_forward();
+    }
 
-        // Compute change sets using key-set operations.
+    /// Diff `current_state` against `previous_state`, populate `self.changes`,
+    /// and forward each change to the logger, stats tracker, and session log.
+    ///
+    /// Split out of [`update`](Self::update) so the initial background scan
+    /// can reuse it once the final batch has landed via
+    /// [`apply_scan_result`](Self::apply_scan_result), instead of diffing
+    /// against incomplete state after every partial batch.
+    pub fn diff_and_forward(&mut self) {
+        self.compute_changes();
+        self.forward_changes();
+    }
+
+    /// Populate `self.changes` by diffing `current_state` against
+    /// `previous_state` over the full key sets. Used by [`update`](Self::update)
+    /// and the initial background scan; [`update_from_events`](Self::update_from_events)
+    /// builds `self.changes` directly from the touched paths instead, since a
+    /// full key-set diff would defeat the point of an incremental update.
+    fn compute_changes(&mut self) {
         let previous_keys: std::collections::HashSet<&PathBuf> =
             self.previous_state.keys().collect();
         let current_keys: std::collections::HashSet<&PathBuf> = self.current_state.keys().collect();
@@ -213,13 +338,24 @@ impl ChangeTracker {
             modified,
             deleted,
         };
+    }
 
-        // Forward events to logger and stats tracker.
+    /// Forward `self.changes` to the logger, stats tracker, and session log,
+    /// then evict deleted paths from the LOC cache. Assumes `self.changes`
+    /// was just populated (by [`compute_changes`](Self::compute_changes) or
+    /// directly by [`update_from_events`](Self::update_from_events)) and that
+    /// `current_state`/`previous_state` still hold the info for every path
+    /// in it.
+    fn forward_changes(&mut self) {
+        // Forward events to logger, stats tracker, and session log.
         for path in &self.changes.added {
             let info = &self.current_state[path];
             let ext = path.extension().and_then(|e| e.to_str());
-            if let Some(ref mut logger) = self.event_logger {
-                logger.log_event(EventType::Created, path, info.size, info.is_dir, info.loc);
+            if !self.recording_paused {
+                if let Some(ref mut logger) = self.event_logger {
+                    logger.log_event(EventType::Created, path, info.size, info.is_dir, info.loc);
+                }
+                Self::log_to_session(&mut self.session_log, EventType::Created, path, info);
             }
             if let Some(ref mut tracker) = self.stats_tracker {
                 tracker.record_event("created", info.size, info.is_dir, ext);
@@ -229,8 +365,11 @@ impl ChangeTracker {
         for path in &self.changes.deleted {
             let info = &self.previous_state[path];
             let ext = path.extension().and_then(|e| e.to_str());
-            if let Some(ref mut logger) = self.event_logger {
-                logger.log_event(EventType::Deleted, path, info.size, info.is_dir, info.loc);
+            if !self.recording_paused {
+                if let Some(ref mut logger) = self.event_logger {
+                    logger.log_event(EventType::Deleted, path, info.size, info.is_dir, info.loc);
+                }
+                Self::log_to_session(&mut self.session_log, EventType::Deleted, path, info);
             }
             if let Some(ref mut tracker) = self.stats_tracker {
                 tracker.record_event("deleted", info.size, info.is_dir, ext);
@@ -240,14 +379,19 @@ impl ChangeTracker {
         for path in &self.changes.modified {
             let info = &self.current_state[path];
             let ext = path.extension().and_then(|e| e.to_str());
-            if let Some(ref mut logger) = self.event_logger {
-                logger.log_event(EventType::Modified, path, info.size, info.is_dir, info.loc);
+            if !self.recording_paused {
+                if let Some(ref mut logger) = self.event_logger {
+                    logger.log_event(EventType::Modified, path, info.size, info.is_dir, info.loc);
+                }
+                Self::log_to_session(&mut self.session_log, EventType::Modified, path, info);
             }
             if let Some(ref mut tracker) = self.stats_tracker {
                 tracker.record_event("modified", info.size, info.is_dir, ext);
             }
         }
 
+        self.detect_repo_events();
+
         // Evict deleted paths from the LOC cache.
         for path in &self.changes.deleted {
             self.loc_cache.remove(path);
@@ -259,6 +403,447 @@ impl ChangeTracker {
             self.initial_scan_done = true;
         }
     }
+
+    /// Append one event to the session log, if one is attached. Logging
+    /// failures are non-fatal -- a session log is a convenience for later
+    /// replay, not the primary recording -- so they're only traced.
+    fn log_to_session(
+        session_log: &mut Option<SessionLog>,
+        event_type: EventType,
+        path: &Path,
+        info: &FileInfo,
+    ) {
+        let Some(log) = session_log else {
+            return;
+        };
+
+        let event = FileEvent {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs_f64(),
+            event_type,
+            path: path.to_string_lossy().into_owned(),
+            size: info.size,
+            is_dir: info.is_dir,
+            loc: info.loc,
+            content_hash: None,
+            branch: None,
+            commit: None,
+            author: None,
+            author_email: None,
+            commit_subject: None,
+            staged: None,
+            unstaged: None,
+            name: None,
+            from_path: None,
+            similarity: None,
+            staged_change: None,
+        };
+
+        if let Err(e) = log.append(&event) {
+            tracing::warn!("failed to append to session log: {e}");
+        }
+    }
+
+    // ------------------------------------------------------------------
+    // Git status
+    // ------------------------------------------------------------------
+
+    /// Merge a freshly read git-status snapshot into the tracker: backfill
+    /// `git_status` on every entry in `current_state` (and any registered
+    /// worktree's entries, which share the same key space), then forward an
+    /// [`EventType::StatusChanged`] event for each path whose status
+    /// actually changed since the last snapshot.
+    pub fn apply_git_status(&mut self, new_statuses: HashMap<PathBuf, GitStatus>) {
+        let mut changed_paths: HashSet<PathBuf> = HashSet::new();
+        for (path, status) in &new_statuses {
+            if self.git_status.get(path) != Some(status) {
+                changed_paths.insert(path.clone());
+            }
+        }
+        for path in self.git_status.keys() {
+            if !new_statuses.contains_key(path) {
+                changed_paths.insert(path.clone());
+            }
+        }
+
+        self.git_status = new_statuses;
+
+        for (path, info) in self.current_state.iter_mut() {
+            info.git_status = self.git_status.get(path).copied();
+        }
+
+        if self.recording_paused {
+            return;
+        }
+
+        for path in &changed_paths {
+            let Some(info) = self.current_state.get(path) else {
+                continue;
+            };
+            if let Some(ref mut logger) = self.event_logger {
+                logger.log_event(
+                    EventType::StatusChanged,
+                    path,
+                    info.size,
+                    info.is_dir,
+                    info.loc,
+                );
+            }
+            Self::log_to_session(&mut self.session_log, EventType::StatusChanged, path, info);
+        }
+    }
+
+    // ------------------------------------------------------------------
+    // Shallow .git watching
+    // ------------------------------------------------------------------
+
+    /// Check whether this round's `self.changes` touched the shallow-scanned
+    /// `.git`, `.git/HEAD`, `.git/index` or `.git/refs/**` paths (see
+    /// `is_git_path_of_interest`),
+    /// and if so, classify the resulting repo event -- a new commit, a
+    /// branch switch, or a checkout to a detached HEAD -- and forward
+    /// it to the `EventLogger`.
+    fn detect_repo_events(&mut self) {
+        let touched_git = self
+            .changes
+            .added
+            .iter()
+            .chain(self.changes.modified.iter())
+            .any(|p| {
+                is_git_metadata_path(p, &self.root_path)
+                    && is_git_path_of_interest(p, &self.root_path)
+            });
+        if !touched_git {
+            return;
+        }
+
+        let Some(state) = crate::git::read_git_state(&self.root_path) else {
+            return;
+        };
+
+        // The first observation just seeds the baseline silently -- mirrors
+        // the live git-state poller in `app.rs`, which only emits once it
+        // has something to compare against.
+        let Some(prev) = self.git_head_baseline.replace(state.clone()) else {
+            return;
+        };
+
+        if prev == state {
+            return;
+        }
+
+        let event_type = if prev.branch != state.branch {
+            if state.branch == "HEAD" {
+                EventType::Checkout
+            } else {
+                EventType::BranchChanged
+            }
+        } else {
+            EventType::Commit
+        };
+
+        if self.recording_paused {
+            return;
+        }
+        if let Some(ref mut logger) = self.event_logger {
+            logger.log_repo_event(event_type, &self.root_path, state.branch, state.commit);
+        }
+    }
+
+    // ------------------------------------------------------------------
+    // Event-driven incremental update
+    // ------------------------------------------------------------------
+
+    /// Apply a coalesced batch of changed absolute paths to `current_state`
+    /// without a full `WalkDir` re-scan, mirroring Zed's
+    /// `process_events(abs_paths)` design: each path is individually
+    /// restat'd and merged in, so a tick where only a handful of paths
+    /// actually changed is O(changed paths) instead of `update`'s
+    /// O(tree size) full re-walk and key-set diff.
+    pub fn update_from_events(&mut self, paths: &[PathBuf]) {
+        let mut added = HashSet::new();
+        let mut deleted = HashSet::new();
+        let mut modified = HashSet::new();
+        let mut removed_info = HashMap::new();
+
+        for path in paths {
+            self.apply_event_path(
+                path,
+                &mut added,
+                &mut deleted,
+                &mut modified,
+                &mut removed_info,
+            );
+        }
+
+        // Changed/created paths already had their pre-update `FileInfo`
+        // rotated into `previous_state` by `apply_event_path`. Deleted paths
+        // never got that chance -- `evict_path` removes them from
+        // `current_state` outright -- so stash their last known info here;
+        // `forward_changes` looks them up in `previous_state` the same way
+        // `update`'s full-state rotation would have left them.
+        self.previous_state.extend(removed_info);
+        self.state_generation += 1;
+        self.changes = ChangeSet {
+            added,
+            modified,
+            deleted,
+        };
+        self.forward_changes();
+    }
+
+    /// Restat a single changed path and merge the result into
+    /// `current_state`, recording the outcome in `added`/`deleted`/`modified`
+    /// and, for anything evicted, its last known `FileInfo` in `removed_info`.
+    fn apply_event_path(
+        &mut self,
+        path: &Path,
+        added: &mut HashSet<PathBuf>,
+        deleted: &mut HashSet<PathBuf>,
+        modified: &mut HashSet<PathBuf>,
+        removed_info: &mut HashMap<PathBuf, FileInfo>,
+    ) {
+        let Some(root) = self.root_for(path) else {
+            return;
+        };
+
+        let meta = match std::fs::symlink_metadata(path) {
+            Ok(m) => m,
+            Err(_) => {
+                self.evict_path(path, deleted, removed_info);
+                return;
+            }
+        };
+
+        if meta.file_type().is_symlink() {
+            self.evict_path(path, deleted, removed_info);
+            return;
+        }
+
+        if self.path_is_filtered(path, &root, meta.is_dir()) {
+            return;
+        }
+
+        let mtime = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs_f64())
+            .unwrap_or(0.0);
+
+        if meta.is_dir() {
+            let is_new = !matches!(self.current_state.get(path), Some(info) if info.is_dir);
+            if let Some(old) = self.current_state.get(path) {
+                self.previous_state.insert(path.to_path_buf(), old.clone());
+            }
+            self.current_state.insert(
+                path.to_path_buf(),
+                FileInfo {
+                    path: path.to_path_buf(),
+                    size: 0,
+                    modified: mtime,
+                    is_dir: true,
+                    loc: 0,
+                    git_status: self.git_status.get(path).copied(),
+                },
+            );
+            if is_new {
+                added.insert(path.to_path_buf());
+                // The watcher doesn't always deliver a separate event for
+                // children that already existed the moment this directory
+                // was created (e.g. `mkdir -p a/b && touch a/b/c` as one
+                // filesystem op), so enumerate them directly. Deeper
+                // descendants get their own create events since `notify`
+                // starts watching newly-created directories recursively.
+                self.walk_new_directory(path, &root, added);
+            } else {
+                modified.insert(path.to_path_buf());
+            }
+            return;
+        }
+
+        let size = meta.len();
+        let prev = self.current_state.get(path).cloned();
+        let changed = match &prev {
+            Some(p) => p.size != size || p.modified != mtime,
+            None => true,
+        };
+
+        let loc = if changed {
+            let new_loc = get_loc(path);
+            self.loc_cache
+                .insert(path.to_path_buf(), (mtime, size, new_loc));
+            new_loc
+        } else {
+            prev.as_ref().map(|p| p.loc).unwrap_or(0)
+        };
+
+        // Rotate the pre-update value into `previous_state`, same as
+        // `update`'s full-state rotation, so Size/Delta and LOC delta
+        // columns compare against "since last refresh" rather than the
+        // session-start snapshot forever.
+        if let Some(ref old) = prev {
+            self.previous_state.insert(path.to_path_buf(), old.clone());
+        }
+
+        self.current_state.insert(
+            path.to_path_buf(),
+            FileInfo {
+                path: path.to_path_buf(),
+                size,
+                modified: mtime,
+                is_dir: false,
+                loc,
+                git_status: self.git_status.get(path).copied(),
+            },
+        );
+
+        match prev {
+            None => {
+                added.insert(path.to_path_buf());
+            }
+            Some(_) if changed => {
+                modified.insert(path.to_path_buf());
+            }
+            Some(_) => {}
+        }
+    }
+
+    /// Remove `path` from `current_state` and `loc_cache`. If it was a
+    /// directory, evicts its whole subtree by path prefix rather than just
+    /// the directory entry itself, since a single "removed" event for a
+    /// directory means everything under it is gone too.
+    fn evict_path(
+        &mut self,
+        path: &Path,
+        deleted: &mut HashSet<PathBuf>,
+        removed_info: &mut HashMap<PathBuf, FileInfo>,
+    ) {
+        let Some(info) = self.current_state.get(path) else {
+            return;
+        };
+
+        if !info.is_dir {
+            if let Some(info) = self.current_state.remove(path) {
+                self.loc_cache.remove(path);
+                deleted.insert(path.to_path_buf());
+                removed_info.insert(path.to_path_buf(), info);
+            }
+            return;
+        }
+
+        let subtree: Vec<PathBuf> = self
+            .current_state
+            .keys()
+            .filter(|p| *p == path || p.starts_with(path))
+            .cloned()
+            .collect();
+        for p in subtree {
+            if let Some(info) = self.current_state.remove(&p) {
+                self.loc_cache.remove(&p);
+                deleted.insert(p.clone());
+                removed_info.insert(p, info);
+            }
+        }
+    }
+
+    /// Return the scan root (`root_path` or a registered worktree) that
+    /// `path` falls under, or `None` if it isn't watched at all.
+    fn root_for(&self, path: &Path) -> Option<PathBuf> {
+        if path.starts_with(&self.root_path) {
+            return Some(self.root_path.clone());
+        }
+        self.worktree_paths
+            .iter()
+            .find(|wt| path.starts_with(wt))
+            .cloned()
+    }
+
+    /// Apply the same hidden/.gitignore/recordings/worktree filtering the
+    /// full scan uses, so an event for an ignored path never enters
+    /// `current_state`.
+    fn path_is_filtered(&self, path: &Path, root: &Path, is_dir: bool) -> bool {
+        if self.use_gitignore && is_git_metadata_path(path, root) {
+            return !is_git_path_of_interest(path, root);
+        }
+        if !self.show_hidden && path_is_hidden(path, root) {
+            return true;
+        }
+        if path_is_recordings(path, root) {
+            return true;
+        }
+        if self.use_gitignore && !path_in_worktree(path, &self.worktree_path_set) {
+            if let Some(ref parser) = self.gitignore_parser {
+                if parser.is_ignored(path, is_dir) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Shallow-walk a newly discovered directory's immediate children and
+    /// merge them into `current_state`. Not recursive -- a nested
+    /// subdirectory found here is recorded as a directory entry, and its own
+    /// children are picked up by the create event `notify` delivers once it
+    /// starts watching it, or by the next full scan otherwise.
+    fn walk_new_directory(&mut self, dir: &Path, root: &Path, added: &mut HashSet<PathBuf>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if meta.file_type().is_symlink() {
+                continue;
+            }
+            if self.path_is_filtered(&path, root, meta.is_dir()) {
+                continue;
+            }
+
+            let mtime = meta
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs_f64())
+                .unwrap_or(0.0);
+
+            if meta.is_dir() {
+                self.current_state.insert(
+                    path.clone(),
+                    FileInfo {
+                        path: path.clone(),
+                        size: 0,
+                        modified: mtime,
+                        is_dir: true,
+                        loc: 0,
+                        git_status: self.git_status.get(&path).copied(),
+                    },
+                );
+            } else {
+                let size = meta.len();
+                let loc = get_loc(&path);
+                self.loc_cache.insert(path.clone(), (mtime, size, loc));
+                self.current_state.insert(
+                    path.clone(),
+                    FileInfo {
+                        path: path.clone(),
+                        size,
+                        modified: mtime,
+                        is_dir: false,
+                        loc,
+                        git_status: self.git_status.get(&path).copied(),
+                    },
+                );
+            }
+            added.insert(path);
+        }
+    }
 }
 
 // ======================================================================
@@ -282,6 +867,35 @@ fn path_is_hidden(path: &Path, root: &Path) -> bool {
     false
 }
 
+/// Returns `true` for `root/.git` itself or anything under it. Does *not*
+/// match a deeper nested repository's own `.git` (e.g. `root/vendor/dep/.git`)
+/// -- only the scan root's directly-owned `.git` is ever special-cased.
+fn is_git_metadata_path(path: &Path, root: &Path) -> bool {
+    let rel = match path.strip_prefix(root) {
+        Ok(r) => r,
+        Err(_) => return false,
+    };
+    rel.components()
+        .next()
+        .is_some_and(|c| c.as_os_str() == ".git")
+}
+
+/// Returns `true` for the handful of `.git` entries chronocode shallow-scans
+/// for commit/branch/checkout detection: `.git` itself, `HEAD`, `index`, and
+/// anything under `refs/`. Deliberately excludes `objects/`, `logs/`,
+/// `hooks/`, and everything else, which can be large and churns for reasons
+/// unrelated to the repo's visible branch/commit state.
+fn is_git_path_of_interest(path: &Path, root: &Path) -> bool {
+    let git_dir = root.join(".git");
+    if path == git_dir {
+        return true;
+    }
+    let Ok(rel) = path.strip_prefix(&git_dir) else {
+        return false;
+    };
+    matches!(rel.to_str(), Some("HEAD") | Some("index")) || rel.starts_with("refs")
+}
+
 /// Returns `true` if the first component of `path` relative to `root` is
 /// `"recordings"`.
 fn path_is_recordings(path: &Path, root: &Path) -> bool {
@@ -312,8 +926,130 @@ fn path_in_worktree(path: &Path, worktree_set: &HashSet<PathBuf>) -> bool {
     false
 }
 
+/// Flush `pending` as an intermediate [`ScanResult`] once it reaches
+/// [`SCAN_BATCH_SIZE`], if a sender was supplied. No-op when `batch_tx` is
+/// `None` (the synchronous scan path doesn't stream batches).
+fn maybe_flush_batch(
+    pending: &mut HashMap<PathBuf, FileInfo>,
+    batch_tx: Option<&mpsc::Sender<ScanResult>>,
+) {
+    let Some(tx) = batch_tx else {
+        return;
+    };
+    if pending.len() < SCAN_BATCH_SIZE {
+        return;
+    }
+    let batch = std::mem::take(pending);
+    let _ = tx.send(ScanResult {
+        state: batch,
+        gitignore_parser: None,
+        partial: true,
+    });
+}
+
+/// Flush `pending` as an intermediate [`ScanResult`] right away, regardless
+/// of [`SCAN_BATCH_SIZE`]. Used after each parallel LOC chunk lands, since a
+/// chunk ([`LOC_CHUNK_SIZE`] entries) is far smaller than the walk's batch
+/// threshold and would otherwise sit unreported until the next file-walk
+/// batch filled up around it.
+fn flush_batch_now(
+    pending: &mut HashMap<PathBuf, FileInfo>,
+    batch_tx: Option<&mpsc::Sender<ScanResult>>,
+) {
+    let Some(tx) = batch_tx else {
+        return;
+    };
+    if pending.is_empty() {
+        return;
+    }
+    let batch = std::mem::take(pending);
+    let _ = tx.send(ScanResult {
+        state: batch,
+        gitignore_parser: None,
+        partial: true,
+    });
+}
+
+/// Number of worker threads to use for parallel LOC counting. Reads
+/// `CHRONOCODE_LOC_WORKERS` if set and valid, falling back to
+/// [`DEFAULT_LOC_POOL_SIZE`] otherwise.
+fn loc_worker_pool_size() -> usize {
+    std::env::var("CHRONOCODE_LOC_WORKERS")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|&n| n > 0)
+        .unwrap_or(DEFAULT_LOC_POOL_SIZE)
+}
+
+/// Count LOC for `jobs` (paths whose cached LOC is missing or stale) across a
+/// bounded pool of worker threads, processing [`LOC_CHUNK_SIZE`] paths at a
+/// time so no single worker claims the whole backlog up front. A path that
+/// never reaches `on_chunk` (e.g. a worker thread panicked) is left for the
+/// next scan to retry.
+///
+/// `jobs` only carries paths here -- the `(mtime, size)` each was captured
+/// against lives in the caller's copy, so a concurrent modification to the
+/// file while this is running can't poison `loc_cache`: the caller inserts
+/// using the metadata it already captured, not whatever the file looks like
+/// by the time counting finishes.
+///
+/// `on_chunk` is called once per completed [`LOC_CHUNK_SIZE`] batch, in
+/// whatever order workers finish them, so the caller can merge counts into
+/// `loc_cache`/`FileInfo` and flush scan progress as soon as each chunk
+/// lands rather than waiting for the whole backlog.
+fn count_loc_parallel(jobs: &[PathBuf], mut on_chunk: impl FnMut(Vec<(PathBuf, usize)>)) {
+    if jobs.is_empty() {
+        return;
+    }
+
+    let pool_size = loc_worker_pool_size().min(jobs.len()).max(1);
+
+    let (job_tx, job_rx) = mpsc::channel::<Vec<PathBuf>>();
+    let job_rx = std::sync::Arc::new(std::sync::Mutex::new(job_rx));
+    for chunk in jobs.chunks(LOC_CHUNK_SIZE) {
+        let _ = job_tx.send(chunk.to_vec());
+    }
+    drop(job_tx);
+
+    let (result_tx, result_rx) = mpsc::channel::<Vec<(PathBuf, usize)>>();
+    let mut workers = Vec::with_capacity(pool_size);
+    for _ in 0..pool_size {
+        let job_rx = std::sync::Arc::clone(&job_rx);
+        let result_tx = result_tx.clone();
+        workers.push(std::thread::spawn(move || loop {
+            let chunk = {
+                let rx = job_rx.lock().unwrap_or_else(|e| e.into_inner());
+                rx.recv()
+            };
+            let Ok(chunk) = chunk else { break };
+            let counted: Vec<(PathBuf, usize)> = chunk
+                .into_iter()
+                .map(|path| {
+                    let loc = get_loc(&path);
+                    (path, loc)
+                })
+                .collect();
+            if result_tx.send(counted).is_err() {
+                break;
+            }
+        }));
+    }
+    drop(result_tx);
+
+    for batch in result_rx {
+        on_chunk(batch);
+    }
+    for worker in workers {
+        let _ = worker.join();
+    }
+}
+
 /// Core scan logic shared by both synchronous and background scan paths.
 ///
+/// When `batch_tx` is `Some`, an intermediate, partial [`ScanResult`] is sent
+/// every [`SCAN_BATCH_SIZE`] entries so a long walk can light up the UI
+/// progressively instead of going silent until it returns.
+///
 /// Returns `(state, gitignore_parser)`.
 #[allow(clippy::too_many_arguments)]
 fn scan_directory_impl(
@@ -325,8 +1061,18 @@ fn scan_directory_impl(
     initial_scan_done: bool,
     loc_cache: &mut HashMap<PathBuf, LocCacheEntry>,
     mut gitignore_parser: Option<GitignoreParser>,
+    batch_tx: Option<&mpsc::Sender<ScanResult>>,
+    git_status: &HashMap<PathBuf, GitStatus>,
 ) -> (HashMap<PathBuf, FileInfo>, Option<GitignoreParser>) {
     let mut state: HashMap<PathBuf, FileInfo> = HashMap::new();
+    let mut pending: HashMap<PathBuf, FileInfo> = HashMap::new();
+    // Files whose LOC needs (re)counting, deferred to a parallel pass after
+    // the walk instead of blocking it file-by-file. `loc_job_meta` carries
+    // each job's captured `(mtime, size)`, so the cache is only ever updated
+    // with the metadata counted against -- not whatever the file looks like
+    // by the time its job runs.
+    let mut loc_jobs: Vec<PathBuf> = Vec::new();
+    let mut loc_job_meta: HashMap<PathBuf, (f64, u64)> = HashMap::new();
 
     // Add the root directory itself.
     if let Ok(meta) = root_path.metadata() {
@@ -337,15 +1083,17 @@ fn scan_directory_impl(
             .map(|d| d.as_secs_f64())
             .unwrap_or(0.0);
 
-        state.insert(
-            root_path.to_path_buf(),
-            FileInfo {
-                size: 0,
-                modified: mtime,
-                is_dir: true,
-                loc: 0,
-            },
-        );
+        let info = FileInfo {
+            path: root_path.to_path_buf(),
+            size: 0,
+            modified: mtime,
+            is_dir: true,
+            loc: 0,
+            git_status: git_status.get(root_path).copied(),
+        };
+        state.insert(root_path.to_path_buf(), info.clone());
+        pending.insert(root_path.to_path_buf(), info);
+        maybe_flush_batch(&mut pending, batch_tx);
     }
 
     let mut walker = WalkDir::new(root_path).follow_links(false).into_iter();
@@ -353,7 +1101,13 @@ fn scan_directory_impl(
     while let Some(entry_result) = walker.next() {
         let entry = match entry_result {
             Ok(e) => e,
-            Err(_) => continue,
+            Err(e) => {
+                tracing::debug!(
+                    "scan: skipping unreadable entry under {}: {e}",
+                    root_path.display()
+                );
+                continue;
+            }
         };
 
         let path = entry.path().to_path_buf();
@@ -368,7 +1122,20 @@ fn scan_directory_impl(
 
         let entry_is_dir = entry.file_type().is_dir();
 
-        if !show_hidden && path_is_hidden(&path, root_path) {
+        // `.git` is hidden like any other dot-directory, except for a
+        // shallow opt-in to HEAD/index/refs so commits and branch switches
+        // are visible in the scan -- see `is_git_path_of_interest`. A nested
+        // repository's own `.git` (e.g. a vendored dependency) is left
+        // fully hidden, matching how worktree discovery never recurses into
+        // a nested repo's metadata either.
+        if use_gitignore && is_git_metadata_path(&path, root_path) {
+            if !is_git_path_of_interest(&path, root_path) {
+                if entry_is_dir {
+                    walker.skip_current_dir();
+                }
+                continue;
+            }
+        } else if !show_hidden && path_is_hidden(&path, root_path) {
             if entry_is_dir {
                 walker.skip_current_dir();
             }
@@ -382,15 +1149,24 @@ fn scan_directory_impl(
             continue;
         }
 
-        // Incrementally load nested .gitignore files.
-        if use_gitignore && entry.file_type().is_file() && entry.file_name() == ".gitignore" {
+        // Incrementally load nested ignore files (`.gitignore`, `.ignore`,
+        // `.chronocodeignore` -- whichever basenames the parser is
+        // configured to honor).
+        if use_gitignore && entry.file_type().is_file() {
             if let Some(ref mut parser) = gitignore_parser {
-                parser.load_gitignore_at(&path);
+                if parser.is_ignore_filename(entry.file_name()) {
+                    parser.load_gitignore_at(&path);
+                }
             }
         }
 
         // Skip gitignored paths; for ignored dirs skip the entire subtree.
-        if use_gitignore && !path_in_worktree(&path, worktree_path_set) {
+        // `.git` itself is never matched against the parser -- it isn't a
+        // real tracked path and some `.gitignore` files exclude it anyway.
+        if use_gitignore
+            && !is_git_metadata_path(&path, root_path)
+            && !path_in_worktree(&path, worktree_path_set)
+        {
             if let Some(ref parser) = gitignore_parser {
                 if parser.is_ignored(&path, entry_is_dir) {
                     if entry_is_dir {
@@ -403,7 +1179,10 @@ fn scan_directory_impl(
 
         let meta = match entry.metadata() {
             Ok(m) => m,
-            Err(_) => continue,
+            Err(e) => {
+                tracing::debug!("scan: skipping {}: {e}", entry.path().display());
+                continue;
+            }
         };
 
         let mtime = meta
@@ -422,36 +1201,40 @@ fn scan_directory_impl(
                 if cached_mtime == mtime && cached_size == size {
                     cached_loc
                 } else {
-                    let new_loc = get_loc(&path);
-                    loc_cache.insert(path.clone(), (mtime, size, new_loc));
-                    new_loc
+                    loc_jobs.push(path.clone());
+                    loc_job_meta.insert(path.clone(), (mtime, size));
+                    cached_loc
                 }
             } else {
-                let new_loc = get_loc(&path);
-                loc_cache.insert(path.clone(), (mtime, size, new_loc));
-                new_loc
+                loc_jobs.push(path.clone());
+                loc_job_meta.insert(path.clone(), (mtime, size));
+                0
             };
 
-            state.insert(
-                path,
-                FileInfo {
-                    size,
-                    modified: mtime,
-                    is_dir: false,
-                    loc,
-                },
-            );
+            let info = FileInfo {
+                path: path.clone(),
+                size,
+                modified: mtime,
+                is_dir: false,
+                loc,
+                git_status: git_status.get(&path).copied(),
+            };
+            state.insert(path.clone(), info.clone());
+            pending.insert(path, info);
         } else if meta.is_dir() {
-            state.insert(
-                path,
-                FileInfo {
-                    size: 0,
-                    modified: mtime,
-                    is_dir: true,
-                    loc: 0,
-                },
-            );
+            let info = FileInfo {
+                path: path.clone(),
+                size: 0,
+                modified: mtime,
+                is_dir: true,
+                loc: 0,
+                git_status: git_status.get(&path).copied(),
+            };
+            state.insert(path.clone(), info.clone());
+            pending.insert(path, info);
         }
+
+        maybe_flush_batch(&mut pending, batch_tx);
     }
 
     // Scan external worktrees.
@@ -472,15 +1255,17 @@ fn scan_directory_impl(
                 .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
                 .map(|d| d.as_secs_f64())
                 .unwrap_or(0.0);
-            state.insert(
-                wt_path.clone(),
-                FileInfo {
-                    size: 0,
-                    modified: mtime,
-                    is_dir: true,
-                    loc: 0,
-                },
-            );
+            let info = FileInfo {
+                path: wt_path.clone(),
+                size: 0,
+                modified: mtime,
+                is_dir: true,
+                loc: 0,
+                git_status: git_status.get(wt_path).copied(),
+            };
+            state.insert(wt_path.clone(), info.clone());
+            pending.insert(wt_path.clone(), info);
+            maybe_flush_batch(&mut pending, batch_tx);
         }
 
         for entry in wt_walker {
@@ -503,7 +1288,10 @@ fn scan_directory_impl(
 
             let meta = match entry.metadata() {
                 Ok(m) => m,
-                Err(_) => continue,
+                Err(e) => {
+                    tracing::debug!("scan: skipping {}: {e}", entry.path().display());
+                    continue;
+                }
             };
 
             let mtime = meta
@@ -522,38 +1310,143 @@ fn scan_directory_impl(
                     if cached_mtime == mtime && cached_size == size {
                         cached_loc
                     } else {
-                        let new_loc = get_loc(&path);
-                        loc_cache.insert(path.clone(), (mtime, size, new_loc));
-                        new_loc
+                        loc_jobs.push(path.clone());
+                        loc_job_meta.insert(path.clone(), (mtime, size));
+                        cached_loc
                     }
                 } else {
-                    let new_loc = get_loc(&path);
-                    loc_cache.insert(path.clone(), (mtime, size, new_loc));
-                    new_loc
+                    loc_jobs.push(path.clone());
+                    loc_job_meta.insert(path.clone(), (mtime, size));
+                    0
                 };
 
-                state.insert(
-                    path,
-                    FileInfo {
-                        size,
-                        modified: mtime,
-                        is_dir: false,
-                        loc,
-                    },
-                );
+                let info = FileInfo {
+                    path: path.clone(),
+                    size,
+                    modified: mtime,
+                    is_dir: false,
+                    loc,
+                    git_status: git_status.get(&path).copied(),
+                };
+                state.insert(path.clone(), info.clone());
+                pending.insert(path, info);
             } else if meta.is_dir() {
-                state.insert(
-                    path,
-                    FileInfo {
-                        size: 0,
-                        modified: mtime,
-                        is_dir: true,
-                        loc: 0,
-                    },
-                );
+                let info = FileInfo {
+                    path: path.clone(),
+                    size: 0,
+                    modified: mtime,
+                    is_dir: true,
+                    loc: 0,
+                    git_status: git_status.get(&path).copied(),
+                };
+                state.insert(path.clone(), info.clone());
+                pending.insert(path, info);
             }
+
+            maybe_flush_batch(&mut pending, batch_tx);
         }
     }
 
+    // Count LOC for everything the walk deferred, across a bounded worker
+    // pool, instead of blocking the walk itself on `get_loc` one file at a
+    // time -- see `count_loc_parallel`. Each chunk's results are merged into
+    // `state`/`loc_cache` and flushed as soon as they land so a big backlog
+    // still reports progress instead of going silent until it's all done.
+    if !loc_jobs.is_empty() {
+        count_loc_parallel(&loc_jobs, |batch| {
+            for (path, loc) in batch {
+                if let Some(&(job_mtime, job_size)) = loc_job_meta.get(&path) {
+                    loc_cache.insert(path.clone(), (job_mtime, job_size, loc));
+                }
+                if let Some(info) = state.get_mut(&path) {
+                    info.loc = loc;
+                    pending.insert(path, info.clone());
+                }
+            }
+            flush_batch_now(&mut pending, batch_tx);
+        });
+    }
+
     (state, gitignore_parser)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn setup_temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("chronocode_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn teardown(dir: &Path) {
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    fn new_tracker(root: PathBuf) -> ChangeTracker {
+        ChangeTracker::new(root, false, false, Vec::new(), false, None, None, None)
+    }
+
+    /// An `update_from_events` tick must compare Size/Delta and LOC against
+    /// the state from the *previous* tick, not the very first scan -- this
+    /// is the bug fixed alongside `previous_state` rotation in
+    /// `apply_event_path`.
+    #[test]
+    fn test_update_from_events_rotates_previous_state_each_tick() {
+        let dir = setup_temp_dir("rotate_test");
+        let file_path = dir.join("a.txt");
+        fs::write(&file_path, "hello").unwrap();
+
+        let mut tracker = new_tracker(dir.clone());
+        tracker.update(&dir);
+
+        fs::write(&file_path, "hello world").unwrap();
+        tracker.update_from_events(&[file_path.clone()]);
+        assert_eq!(
+            tracker.previous_state.get(&file_path).unwrap().size,
+            "hello".len() as u64
+        );
+        assert_eq!(
+            tracker.current_state.get(&file_path).unwrap().size,
+            "hello world".len() as u64
+        );
+
+        fs::write(&file_path, "hello world, again!").unwrap();
+        tracker.update_from_events(&[file_path.clone()]);
+        assert_eq!(
+            tracker.previous_state.get(&file_path).unwrap().size,
+            "hello world".len() as u64,
+            "previous_state should hold the size from just before this tick, \
+             not the session-start snapshot"
+        );
+        assert_eq!(
+            tracker.current_state.get(&file_path).unwrap().size,
+            "hello world, again!".len() as u64
+        );
+
+        teardown(&dir);
+    }
+
+    /// Re-stat'ing an already-known directory should rotate its prior entry
+    /// into `previous_state` the same way a changed file does, not just
+    /// files -- `apply_event_path`'s directory branch has its own rotation.
+    #[test]
+    fn test_update_from_events_rotates_previous_state_for_directories() {
+        let dir = setup_temp_dir("rotate_dir_test");
+        let sub = dir.join("sub");
+        fs::create_dir_all(&sub).unwrap();
+
+        let mut tracker = new_tracker(dir.clone());
+        tracker.update(&dir);
+        assert!(!tracker.previous_state.contains_key(&sub));
+
+        tracker.update_from_events(&[sub.clone()]);
+        assert!(tracker.previous_state.contains_key(&sub));
+        assert!(tracker.previous_state.get(&sub).unwrap().is_dir);
+
+        teardown(&dir);
+    }
+}
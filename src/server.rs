@@ -1,53 +1,253 @@
-use std::process::{Command, Stdio};
-use std::time::Duration;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
 
 use anyhow::Result;
 
+use crate::live::{LiveMessage, LiveServer};
+
 /// The replay viewer HTML, embedded into the binary at compile time.
 const REPLAY_HTML: &str = include_str!("../replay.html");
 
-/// Spin up a local HTTP server, open the viewer in the browser, wait for it to
-/// load, then tear down the server.
+/// Guess a `Content-Type` header from a request path's extension. Falls
+/// back to a generic binary type for anything this viewer doesn't serve.
+fn content_type_for(path: &str) -> &'static str {
+    match Path::new(path).extension().and_then(|e| e.to_str()) {
+        Some("html") | Some("htm") => "text/html; charset=utf-8",
+        Some("js") => "text/javascript; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("json") => "application/json",
+        Some("svg") => "image/svg+xml",
+        _ => "application/octet-stream",
+    }
+}
+
+/// A parsed HTTP/1.1 request: the requested path plus any `Range` header
+/// value. The body, if any, is never read -- this server only ever sees
+/// simple GETs from a browser loading the viewer's own static assets,
+/// opening the live stream, or seeking into a recording.
+struct Request {
+    path: String,
+    range: Option<String>,
+}
+
+/// Read the request line and headers of a raw HTTP/1.1 request, stopping
+/// at the blank line that ends the header block.
+fn read_request(stream: &TcpStream) -> Option<Request> {
+    let mut reader = BufReader::new(stream);
+
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let path = line.split_whitespace().nth(1)?.to_string();
+
+    let mut range = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).ok()? == 0 {
+            break;
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("range") {
+                range = Some(value.trim().to_string());
+            }
+        }
+    }
+
+    Some(Request { path, range })
+}
+
+/// Parse a `Range: bytes=start-end` header -- the only form browsers emit
+/// when seeking -- into an inclusive `(start, end)` byte range clamped to
+/// `len`. Returns `None` for anything else (multi-range, unsatisfiable,
+/// malformed), so the caller can fall back to a full `200` response.
+fn parse_range(header: &str, len: u64) -> Option<(u64, u64)> {
+    if len == 0 {
+        return None;
+    }
+    let last = len - 1;
+    let (start, end) = header.strip_prefix("bytes=")?.split_once('-')?;
+
+    if start.is_empty() {
+        // Suffix range: the last `end` bytes of the resource.
+        let suffix_len: u64 = end.parse().ok().filter(|&n| n > 0)?;
+        return Some((len.saturating_sub(suffix_len), last));
+    }
+
+    let start: u64 = start.parse().ok()?;
+    let end = if end.is_empty() {
+        last
+    } else {
+        end.parse::<u64>().ok()?.min(last)
+    };
+    (start <= end).then_some((start, end))
+}
+
+/// Write a complete, non-streaming HTTP response and close the connection.
+fn write_response(mut stream: TcpStream, status: &str, content_type: &str, body: &str) {
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len(),
+    );
+    let _ = stream.write_all(response.as_bytes());
+}
+
+/// Serve one `/events` connection as Server-Sent Events: push the current
+/// snapshot as the first frame, then forward every subsequent delta/resync
+/// message broadcast by `live` as its own `data: ...` frame, riding the same
+/// snapshot-then-deltas protocol the WebSocket path in `live.rs` uses, until
+/// the browser disconnects.
+fn handle_sse_connection(mut stream: TcpStream, live: &LiveServer) {
+    let (snapshot, queue) = live.subscribe();
+
+    let headers = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n";
+    if stream.write_all(headers.as_bytes()).is_err() {
+        return;
+    }
+
+    let snapshot_frame = format!("data: {}\n\n", LiveMessage::Snapshot(snapshot).to_json());
+    if stream.write_all(snapshot_frame.as_bytes()).is_err() {
+        return;
+    }
+
+    loop {
+        let pending = queue.drain_blocking();
+        if pending.is_empty() && queue.is_closed() {
+            break;
+        }
+        for msg in pending {
+            let frame = format!("data: {}\n\n", msg.to_json());
+            if stream.write_all(frame.as_bytes()).is_err() {
+                queue.mark_closed();
+                return;
+            }
+        }
+    }
+}
+
+/// Serve one `/recording.json` connection: read the recording file from
+/// disk and return it whole, or -- when the request carries a `Range`
+/// header -- just the requested slice as a `206 Partial Content`, so the
+/// viewer can seek into a large session instead of downloading (and
+/// decompressing) the whole thing up front.
+fn handle_recording_request(stream: TcpStream, path: &Path, range: Option<&str>) {
+    let Ok(body) = fs::read(path) else {
+        write_response(stream, "404 Not Found", "text/plain; charset=utf-8", "404 Not Found");
+        return;
+    };
+    let len = body.len() as u64;
+    let range = range.and_then(|r| parse_range(r, len));
+    write_recording_response(stream, &body, range);
+}
+
+/// Write the response for `/recording.json`: a `200` with the full body,
+/// or a `206 Partial Content` for `(start, end)` with the matching
+/// `Content-Range` header, per the subset of RFC 7233 a browser's `fetch`
+/// range requests actually use.
+fn write_recording_response(mut stream: TcpStream, body: &[u8], range: Option<(u64, u64)>) {
+    let total = body.len() as u64;
+    let (status, slice, content_range) = match range {
+        Some((start, end)) => (
+            "206 Partial Content",
+            &body[start as usize..=end as usize],
+            format!("Content-Range: bytes {start}-{end}/{total}\r\n"),
+        ),
+        None => ("200 OK", body, String::new()),
+    };
+
+    let header = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nAccept-Ranges: bytes\r\n{content_range}Content-Length: {}\r\nConnection: close\r\n\r\n",
+        slice.len(),
+    );
+    if stream.write_all(header.as_bytes()).is_err() {
+        return;
+    }
+    let _ = stream.write_all(slice);
+}
+
+/// Serve one connection: `/events` streams live file events (when a
+/// [`LiveServer`] was supplied), `/recording.json` streams a finished
+/// recording from disk with range support (when one was supplied),
+/// everything else resolves against `files` and gets a `200` with the
+/// matching body or a `404`.
+fn handle_connection(
+    stream: TcpStream,
+    files: &HashMap<&'static str, &'static str>,
+    live: Option<&LiveServer>,
+    recording: Option<&Path>,
+) {
+    let Some(req) = read_request(&stream) else {
+        return;
+    };
+
+    if req.path == "/events" {
+        match live {
+            Some(live) => handle_sse_connection(stream, live),
+            None => write_response(stream, "404 Not Found", "text/plain; charset=utf-8", "404 Not Found"),
+        }
+        return;
+    }
+
+    if req.path == "/recording.json" {
+        match recording {
+            Some(path) => handle_recording_request(stream, path, req.range.as_deref()),
+            None => write_response(stream, "404 Not Found", "text/plain; charset=utf-8", "404 Not Found"),
+        }
+        return;
+    }
+
+    let lookup: &str = if req.path == "/" { "/index.html" } else { &req.path };
+    let (status, body): (&str, &str) = match files.get(lookup) {
+        Some(body) => ("200 OK", body),
+        None => ("404 Not Found", "404 Not Found"),
+    };
+    write_response(stream, status, content_type_for(lookup), body);
+}
+
+/// Spin up a small embedded HTTP server -- no external `python3`/`npx`
+/// dependency required -- that serves `REPLAY_HTML` straight from memory,
+/// open the viewer in the browser against it, then keep serving until the
+/// process is interrupted (Ctrl+C) instead of tearing the server down
+/// after a guessed delay. When `live` is given, the server also exposes a
+/// `/events` Server-Sent Events endpoint streaming that session's file
+/// events as they happen, so the viewer's timeline animates live instead
+/// of loading a fixed dump.
 ///
 /// If `url_fragment` is `Some(frag)`, the opened URL will be
 /// `http://127.0.0.1:{port}/#{frag}`.  Otherwise, the root URL is opened.
-pub fn serve_and_open(url_fragment: Option<&str>) -> Result<()> {
-    // Write the embedded HTML to a temp directory.
-    let tmp_dir = std::env::temp_dir().join("chronocode-viewer");
-    std::fs::create_dir_all(&tmp_dir)?;
-    std::fs::write(tmp_dir.join("index.html"), REPLAY_HTML)?;
-
-    // Pick a free port.
-    let port = {
-        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
-        listener.local_addr()?.port()
-    };
+fn run_server(
+    live: Option<Arc<LiveServer>>,
+    recording: Option<Arc<PathBuf>>,
+    url_fragment: Option<&str>,
+) -> Result<()> {
+    let mut files = HashMap::new();
+    files.insert("/index.html", REPLAY_HTML);
+    let files = Arc::new(files);
+
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let port = listener.local_addr()?.port();
 
-    // Spawn a local server. Try python3 first, then npx serve.
-    let mut server = Command::new("python3")
-        .args([
-            "-m",
-            "http.server",
-            &port.to_string(),
-            "--bind",
-            "127.0.0.1",
-        ])
-        .current_dir(&tmp_dir)
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .spawn()
-        .or_else(|_| {
-            Command::new("npx")
-                .args(["serve", "-l", &port.to_string(), "-s", "."])
-                .current_dir(&tmp_dir)
-                .stdout(Stdio::null())
-                .stderr(Stdio::null())
-                .spawn()
-        })
-        .map_err(|_| anyhow::anyhow!("Could not start a local server (need python3 or npx)"))?;
-
-    // Give the server a moment to bind.
-    std::thread::sleep(Duration::from_millis(300));
+    let accept_files = files.clone();
+    let accept_live = live.clone();
+    let accept_recording = recording.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else { continue };
+            let files = accept_files.clone();
+            let live = accept_live.clone();
+            let recording = accept_recording.clone();
+            thread::spawn(move || {
+                handle_connection(stream, &files, live.as_deref(), recording.as_deref().map(PathBuf::as_path))
+            });
+        }
+    });
 
     let url = match url_fragment {
         Some(frag) => format!("http://127.0.0.1:{}/#{}", port, frag),
@@ -56,12 +256,33 @@ pub fn serve_and_open(url_fragment: Option<&str>) -> Result<()> {
 
     println!("Opening viewer at http://127.0.0.1:{} ...", port);
     open::that(&url)?;
+    println!("Viewer server running -- press Ctrl+C to stop.");
 
-    // Give the browser time to load the page and all assets,
-    // then tear down the server. Once loaded, the page is self-contained.
-    std::thread::sleep(Duration::from_secs(3));
-    let _ = server.kill();
-    let _ = std::fs::remove_dir_all(&tmp_dir);
+    // Keep serving instead of guessing how long the browser needs to finish
+    // loading and tearing the server down on a timer.
+    loop {
+        thread::park();
+    }
+}
+
+/// Serve a one-shot static replay: the viewer loads its fixed dump once and
+/// is self-contained from then on.
+pub fn serve_and_open(url_fragment: Option<&str>) -> Result<()> {
+    run_server(None, None, url_fragment)
+}
+
+/// Serve the viewer alongside a running [`LiveServer`], exposing `/events`
+/// so the page can stream the session's file events as they happen instead
+/// of loading a fixed dump.
+pub fn serve_live_and_open(live: Arc<LiveServer>, url_fragment: Option<&str>) -> Result<()> {
+    run_server(Some(live), None, url_fragment)
+}
 
-    Ok(())
+/// Serve a finished recording straight from disk at `/recording.json`,
+/// honoring `Range` requests so the viewer can seek into a large session
+/// instead of inlining the whole (decompressed) thing into the URL. Use
+/// this instead of [`serve_and_open`] once a recording is too big for a
+/// `#data=...` fragment to carry comfortably.
+pub fn serve_recording_and_open(recording_path: PathBuf, url_fragment: Option<&str>) -> Result<()> {
+    run_server(None, Some(Arc::new(recording_path)), url_fragment)
 }
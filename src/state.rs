@@ -17,6 +17,37 @@ pub enum EventType {
     Created,
     Modified,
     Deleted,
+    /// A synthetic event recording a branch switch or new commit, rather
+    /// than a filesystem change. Carries its details in [`FileEvent`]'s
+    /// `branch`/`commit`/`staged`/`unstaged` fields; `path` names the
+    /// repository or worktree it came from.
+    Git,
+    /// A user-dropped marker at a moment of interest, named in [`FileEvent`]'s
+    /// `name` field, for the web viewer to offer jump-to-marker navigation.
+    Bookmark,
+    /// A path's [`crate::git::GitStatus`] changed (e.g. staged, became
+    /// untracked, or went back to clean) since the last git-status refresh.
+    StatusChanged,
+    /// The current branch's tip moved to a new commit, detected from a
+    /// shallow watch of `.git/refs/**`. Carries the new commit in
+    /// [`FileEvent`]'s `commit` field.
+    Commit,
+    /// `.git/HEAD` started pointing at a different branch ref. Carries the
+    /// new branch name and its current commit.
+    BranchChanged,
+    /// `.git/HEAD` went from a branch ref to a detached commit. Leaving a
+    /// detached HEAD for a branch is reported as
+    /// [`BranchChanged`](Self::BranchChanged) instead.
+    Checkout,
+    /// A path was renamed or moved, detected from a `git diff-tree -M`
+    /// rename pair rather than a delete+create. Carries the origin in
+    /// [`FileEvent`]'s `from_path` field (`path` holds the destination) and
+    /// git's similarity score in `similarity`.
+    Renamed,
+    /// A path was copied from another, detected from a `git diff-tree -C`
+    /// copy pair. Same fields as [`Renamed`](Self::Renamed), except the
+    /// origin path keeps existing instead of disappearing.
+    Copied,
 }
 
 // ---------------------------------------------------------------------------
@@ -30,6 +61,10 @@ pub struct FileInfo {
     pub modified: f64,
     pub is_dir: bool,
     pub loc: usize,
+    /// This path's working-tree git status, if it has one and a git-status
+    /// refresh has run. `None` means either "not in a git repo", "clean", or
+    /// "not refreshed yet" -- `ChangeTracker` doesn't distinguish those.
+    pub git_status: Option<crate::git::GitStatus>,
 }
 
 impl Default for FileInfo {
@@ -40,6 +75,7 @@ impl Default for FileInfo {
             modified: 0.0,
             is_dir: false,
             loc: 0,
+            git_status: None,
         }
     }
 }
@@ -60,8 +96,56 @@ pub struct FileEvent {
     pub is_dir: bool,
     #[serde(default)]
     pub loc: usize,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub content: Option<String>,
+    /// Hash of this event's captured text content in the recording's
+    /// content-addressed blob store (see `EventLogger::blobs`), set when
+    /// `--content` is enabled and this is a created/modified text file
+    /// event. The content itself is never duplicated inline here.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    /// Branch name, set on [`EventType::Git`] events, and the source
+    /// branch/worktree label on events from
+    /// `git::generate_combined_recording`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Commit hash, set on [`EventType::Git`] events (full HEAD hash) and on
+    /// events generated from git history by `git::generate_recording`
+    /// (abbreviated hash of the commit the change came from).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Commit author's name, set on events generated from git history.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Commit author's email, set on events generated from git history.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_email: Option<String>,
+    /// Commit subject line, set on events generated from git history. Lets
+    /// a viewer caption a frame with the commit message instead of just its
+    /// hash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_subject: Option<String>,
+    /// Staged file count, set on [`EventType::Git`] events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub staged: Option<usize>,
+    /// Unstaged file count, set on [`EventType::Git`] events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unstaged: Option<usize>,
+    /// Bookmark label, set on [`EventType::Bookmark`] events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Origin path, set on [`EventType::Renamed`]/[`EventType::Copied`]
+    /// events. `path` holds the destination.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_path: Option<String>,
+    /// Git's rename/copy similarity score (0-100), e.g. the `92` in `R092`,
+    /// set on [`EventType::Renamed`]/[`EventType::Copied`] events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<u8>,
+    /// `Some(true)`/`Some(false)` for a staged/unstaged change, set on the
+    /// synthetic closing-frame events `git::generate_recording` appends for
+    /// the repository's current uncommitted work (see
+    /// `git::append_working_tree_events`). `None` on every other event.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub staged_change: Option<bool>,
 }
 
 // ---------------------------------------------------------------------------
@@ -182,6 +266,68 @@ pub fn format_delta(value: i64, is_size: bool) -> (String, &'static str) {
     (formatted, color)
 }
 
+/// How to render byte sizes and size deltas in the tree's Size/Delta columns
+/// and the stats dashboard, set via `--compact-sizes`/`--decimal-sizes`.
+/// LOC columns are unaffected -- they're already plain integers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SizeFormat {
+    /// Render as `1.2k`/`3.4M`/`5.6G` (`du -h` style) instead of the default
+    /// `1.2 KB`/`3.4 MB` spelled-out units.
+    pub compact: bool,
+    /// Use a 1000-byte unit instead of the default 1024-byte one. Only
+    /// affects `compact` output -- the spelled-out style is always binary.
+    pub decimal: bool,
+}
+
+/// Format a byte count the `compact` way: `1.2k`, `3.4M`, `5.6G`, base 1024
+/// or 1000 depending on `decimal`. The non-compact sibling of `format_size`.
+pub fn format_size_compact(size_bytes: u64, decimal: bool) -> String {
+    const UNITS: &[&str] = &["", "k", "M", "G", "T"];
+    let base: f64 = if decimal { 1000.0 } else { 1024.0 };
+
+    let mut size = size_bytes as f64;
+    let mut unit_index = 0;
+    while size >= base && unit_index < UNITS.len() - 1 {
+        size /= base;
+        unit_index += 1;
+    }
+
+    if unit_index == 0 {
+        format!("{}", size_bytes)
+    } else if size < 10.0 {
+        format!("{:.1}{}", size, UNITS[unit_index])
+    } else {
+        format!("{:.0}{}", size, UNITS[unit_index])
+    }
+}
+
+/// `format_size`, but honoring a caller-selected `SizeFormat` instead of
+/// always using the spelled-out style.
+pub fn format_size_with(size_bytes: u64, fmt: SizeFormat) -> String {
+    if fmt.compact {
+        format_size_compact(size_bytes, fmt.decimal)
+    } else {
+        format_size(size_bytes)
+    }
+}
+
+/// `format_delta`, but honoring a caller-selected `SizeFormat` for `is_size`
+/// deltas; LOC deltas (`is_size: false`) are unaffected by `fmt`.
+pub fn format_delta_with(value: i64, is_size: bool, fmt: SizeFormat) -> (String, &'static str) {
+    if !is_size {
+        return format_delta(value, is_size);
+    }
+    if value == 0 {
+        return (String::new(), "dim");
+    }
+
+    let color = if value > 0 { "green" } else { "red" };
+    let sign = if value > 0 { "+" } else { "-" };
+    let abs = value.unsigned_abs();
+
+    (format!("{}{}", sign, format_size_with(abs, fmt)), color)
+}
+
 /// Set of extensions considered to be text/source files.
 const TEXT_EXTENSIONS: &[&str] = &[
     "py",
@@ -308,6 +454,29 @@ pub fn format_loc(loc: usize) -> String {
     }
 }
 
+/// Format a unix-epoch `modified` timestamp relative to `now` (also unix
+/// seconds), `git log --relative`-style, for the tree's opt-in `--long`
+/// Modified column:
+/// - < 1m   -> "just now"
+/// - < 1h   -> "5m ago"
+/// - < 1d   -> "3h ago"
+/// - < 2d   -> "yesterday"
+/// - else   -> "12d ago"
+pub fn format_relative_time(now: f64, modified: f64) -> String {
+    let delta = (now - modified).max(0.0) as i64;
+    if delta < 60 {
+        "just now".into()
+    } else if delta < 3_600 {
+        format!("{}m ago", delta / 60)
+    } else if delta < 86_400 {
+        format!("{}h ago", delta / 3_600)
+    } else if delta < 2 * 86_400 {
+        "yesterday".into()
+    } else {
+        format!("{}d ago", delta / 86_400)
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Conversion helpers for EventType
 // ---------------------------------------------------------------------------
@@ -319,6 +488,14 @@ impl EventType {
             EventType::Created => "created",
             EventType::Modified => "modified",
             EventType::Deleted => "deleted",
+            EventType::Git => "git",
+            EventType::Bookmark => "bookmark",
+            EventType::StatusChanged => "statuschanged",
+            EventType::Commit => "commit",
+            EventType::BranchChanged => "branchchanged",
+            EventType::Checkout => "checkout",
+            EventType::Renamed => "renamed",
+            EventType::Copied => "copied",
         }
     }
 }
@@ -391,6 +568,31 @@ mod tests {
         assert_eq!(format_delta(-1024, true), ("-1.0 KB".to_string(), "red"));
     }
 
+    #[test]
+    fn test_format_size_compact() {
+        assert_eq!(format_size_compact(0, false), "0");
+        assert_eq!(format_size_compact(512, false), "512");
+        assert_eq!(format_size_compact(1536, false), "1.5k");
+        assert_eq!(format_size_compact(1_048_576, false), "1.0M");
+        assert_eq!(format_size_compact(1_073_741_824, false), "1.0G");
+        assert_eq!(format_size_compact(1_000, true), "1.0k");
+        assert_eq!(format_size_compact(1_500_000, true), "1.5M");
+    }
+
+    #[test]
+    fn test_format_delta_with_compact() {
+        let compact = SizeFormat { compact: true, decimal: false };
+        assert_eq!(format_delta_with(2048, true, compact), ("+2.0k".to_string(), "green"));
+        assert_eq!(format_delta_with(-1024, true, compact), ("-1.0k".to_string(), "red"));
+        // LOC deltas ignore `fmt` entirely.
+        assert_eq!(format_delta_with(42, false, compact), ("+42".to_string(), "green"));
+        // Default (non-compact) `fmt` matches plain `format_delta`.
+        assert_eq!(
+            format_delta_with(2048, true, SizeFormat::default()),
+            format_delta(2048, true)
+        );
+    }
+
     #[test]
     fn test_format_loc() {
         assert_eq!(format_loc(0), "");
@@ -399,6 +601,19 @@ mod tests {
         assert_eq!(format_loc(2_500_000), "2ML");
     }
 
+    #[test]
+    fn test_format_relative_time() {
+        let now = 1_000_000.0;
+        assert_eq!(format_relative_time(now, now), "just now");
+        assert_eq!(format_relative_time(now, now - 30.0), "just now");
+        assert_eq!(format_relative_time(now, now - 300.0), "5m ago");
+        assert_eq!(format_relative_time(now, now - 7_200.0), "2h ago");
+        assert_eq!(format_relative_time(now, now - 90_000.0), "yesterday");
+        assert_eq!(format_relative_time(now, now - 10 * 86_400.0), "10d ago");
+        // A `modified` timestamp in the future (clock skew) clamps to "just now".
+        assert_eq!(format_relative_time(now, now + 60.0), "just now");
+    }
+
     #[test]
     fn test_get_file_emoji() {
         assert_eq!(get_file_emoji("dir", true), "\u{1F4C1}");
@@ -1,6 +1,15 @@
 use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::state::FileEvent;
+
+#[derive(Serialize, Deserialize)]
 pub struct Stats {
     pub session_duration: f64,
     pub total_created: u64,
@@ -213,3 +222,80 @@ impl StatisticsTracker {
         }
     }
 }
+
+/// Output format for [`Stats::to_report`].
+#[derive(Clone, Copy, Debug)]
+pub enum ReportFormat {
+    Json,
+    Yaml,
+}
+
+impl Stats {
+    /// Render this session's summary -- duration, totals, peak counts,
+    /// activity buckets, and top extensions -- as JSON or YAML, for piping
+    /// into scripts or CI dashboards instead of only the human-readable
+    /// `println!` summary printed at exit.
+    pub fn to_report(&self, format: ReportFormat) -> Result<String> {
+        match format {
+            ReportFormat::Json => {
+                serde_json::to_string_pretty(self).context("serializing stats report as JSON")
+            }
+            ReportFormat::Yaml => serde_yaml::to_string(self).context("serializing stats report as YAML"),
+        }
+    }
+}
+
+/// Append-only JSONL log of every `FileEvent` in a session, independent of
+/// the full recording file: one JSON object per line (the same
+/// `FileEvent::to_json`), flushed immediately so the log survives a killed
+/// process. Use [`load_session_log`] to rebuild a `StatisticsTracker` from
+/// it later.
+pub struct SessionLog {
+    file: File,
+}
+
+impl SessionLog {
+    /// Open `path` for appending, creating it if it doesn't exist yet.
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("opening session log {}", path.display()))?;
+        Ok(Self { file })
+    }
+
+    /// Append one event as a single JSON line.
+    pub fn append(&mut self, event: &FileEvent) -> Result<()> {
+        let mut line = serde_json::to_string(&event.to_json())?;
+        line.push('\n');
+        self.file
+            .write_all(line.as_bytes())
+            .context("writing to session log")
+    }
+}
+
+/// Rebuild a `StatisticsTracker` by replaying a saved JSONL session log
+/// through `record_event`, so a persisted session's stats can be recomputed
+/// without re-running the watch session that produced it. The resulting
+/// tracker's `session_duration` is measured from the moment of replay, not
+/// the original session -- callers after a specific duration should use the
+/// log's own first/last event timestamps instead.
+pub fn load_session_log(path: &Path) -> Result<StatisticsTracker> {
+    let file =
+        File::open(path).with_context(|| format!("opening session log {}", path.display()))?;
+    let mut tracker = StatisticsTracker::new();
+
+    for line in BufReader::new(file).lines() {
+        let line = line.context("reading session log")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event: FileEvent =
+            serde_json::from_str(&line).context("parsing session log event")?;
+        let ext = Path::new(&event.path).extension().and_then(|e| e.to_str());
+        tracker.record_event(event.event_type.as_str(), event.size, event.is_dir, ext);
+    }
+
+    Ok(tracker)
+}
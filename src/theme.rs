@@ -0,0 +1,194 @@
+//! Runtime-loadable color theme.
+//!
+//! Every `render_*` helper in [`crate::renderer`] took `ratatui::style::Color`
+//! literals directly (`Color::Cyan`, `Color::Green`, ...) before this module
+//! existed, the way the rest of the UI was written by hand as it grew. That's
+//! fine until someone wants a light palette or to match a terminal scheme --
+//! so colors are now grouped into semantic roles on a `Theme`, loadable from
+//! a TOML file at startup like an editor's color scheme, falling back to the
+//! built-in cyan scheme for any role the file doesn't mention.
+
+use ratatui::style::Color;
+use serde::Deserialize;
+use std::path::Path;
+
+/// Semantic color roles used throughout the renderer. Field values are color
+/// names in the same format `color_from_name` already accepted for
+/// size/delta coloring (`"cyan"`, `"dim"`, ...), extended here to also accept
+/// `#rrggbb` hex and 256-color indices (e.g. `"208"`).
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    /// Added/new entries -- tree rows, the legend key, the stats dashboard.
+    pub created: String,
+    /// Modified entries -- tree rows, the legend key, the stats dashboard.
+    pub modified: String,
+    /// Deleted entries -- tree rows, the legend key, the stats dashboard,
+    /// and watcher error text.
+    pub deleted: String,
+    /// Default directory name color (files keep the plain foreground).
+    pub dir: String,
+    /// Cyan-ish accent used for header chrome, panel borders, and active
+    /// search/filter highlighting.
+    pub header_border: String,
+    /// Dim secondary text -- field labels, placeholders, disabled hints.
+    pub legend_dim: String,
+    /// The `[start-end/total]` scroll position indicator in the legend bar.
+    pub scroll_indicator: String,
+    /// Border/title color for the diagnostics log panel, kept distinct from
+    /// `header_border` so the two side panels read as separate regions.
+    pub log_border: String,
+    /// Colors the tree's depth-guide indentation cycles through when
+    /// `--no-rainbow-edges` isn't set, one per nesting level, wrapping once
+    /// exhausted. See `renderer::prefix_spans`.
+    pub rainbow_palette: Vec<String>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            created: "green".into(),
+            modified: "yellow".into(),
+            deleted: "red".into(),
+            dir: "white".into(),
+            header_border: "cyan".into(),
+            legend_dim: "dim".into(),
+            scroll_indicator: "cyan".into(),
+            log_border: "magenta".into(),
+            rainbow_palette: vec![
+                "cyan".into(),
+                "magenta".into(),
+                "blue".into(),
+                "green".into(),
+            ],
+        }
+    }
+}
+
+impl Theme {
+    /// Load a theme from a TOML file. Any role the file omits keeps its
+    /// built-in default (see the `#[serde(default)]` container attribute
+    /// above), so a user only needs to override the roles they care about.
+    pub fn load(path: &Path) -> std::io::Result<Theme> {
+        let text = std::fs::read_to_string(path)?;
+        toml::from_str(&text)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))
+    }
+
+    pub fn created_color(&self) -> Color {
+        color_from_name(&self.created)
+    }
+
+    pub fn modified_color(&self) -> Color {
+        color_from_name(&self.modified)
+    }
+
+    pub fn deleted_color(&self) -> Color {
+        color_from_name(&self.deleted)
+    }
+
+    pub fn dir_color(&self) -> Color {
+        color_from_name(&self.dir)
+    }
+
+    pub fn header_border_color(&self) -> Color {
+        color_from_name(&self.header_border)
+    }
+
+    pub fn legend_dim_color(&self) -> Color {
+        color_from_name(&self.legend_dim)
+    }
+
+    pub fn scroll_indicator_color(&self) -> Color {
+        color_from_name(&self.scroll_indicator)
+    }
+
+    pub fn log_border_color(&self) -> Color {
+        color_from_name(&self.log_border)
+    }
+
+    /// The rainbow depth-guide palette, resolved to `Color`s. Falls back to
+    /// a single dark-gray entry if the theme file set an empty list, so
+    /// `palette[level % palette.len()]` callers never divide by zero.
+    pub fn rainbow_palette_colors(&self) -> Vec<Color> {
+        if self.rainbow_palette.is_empty() {
+            return vec![Color::DarkGray];
+        }
+        self.rainbow_palette.iter().map(|name| color_from_name(name)).collect()
+    }
+}
+
+/// Resolve a color name to a ratatui `Color`. Accepts the original fixed set
+/// of named colors, a `#rrggbb` hex triplet, or a bare 256-color index (e.g.
+/// `"208"`), falling back to `Color::Reset` for anything else.
+pub(crate) fn color_from_name(name: &str) -> Color {
+    if let Some(hex) = name.strip_prefix('#') {
+        if hex.len() == 6 {
+            if let Ok(v) = u32::from_str_radix(hex, 16) {
+                let r = ((v >> 16) & 0xFF) as u8;
+                let g = ((v >> 8) & 0xFF) as u8;
+                let b = (v & 0xFF) as u8;
+                return Color::Rgb(r, g, b);
+            }
+        }
+    }
+
+    if let Ok(idx) = name.parse::<u8>() {
+        return Color::Indexed(idx);
+    }
+
+    match name {
+        "dim" => Color::DarkGray,
+        "cyan" => Color::Cyan,
+        "yellow" => Color::Yellow,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "white" => Color::White,
+        _ => Color::Reset,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_from_name_named() {
+        assert_eq!(color_from_name("dim"), Color::DarkGray);
+        assert_eq!(color_from_name("cyan"), Color::Cyan);
+        assert_eq!(color_from_name("unknown"), Color::Reset);
+    }
+
+    #[test]
+    fn test_color_from_name_hex() {
+        assert_eq!(color_from_name("#ff8800"), Color::Rgb(0xff, 0x88, 0x00));
+    }
+
+    #[test]
+    fn test_color_from_name_indexed() {
+        assert_eq!(color_from_name("208"), Color::Indexed(208));
+    }
+
+    #[test]
+    fn test_theme_default_matches_builtin_scheme() {
+        let theme = Theme::default();
+        assert_eq!(theme.created_color(), Color::Green);
+        assert_eq!(theme.deleted_color(), Color::Red);
+        assert_eq!(theme.header_border_color(), Color::Cyan);
+        assert_eq!(
+            theme.rainbow_palette_colors(),
+            vec![Color::Cyan, Color::Magenta, Color::Blue, Color::Green]
+        );
+    }
+
+    #[test]
+    fn test_rainbow_palette_colors_empty_falls_back() {
+        let theme = Theme {
+            rainbow_palette: Vec::new(),
+            ..Theme::default()
+        };
+        assert_eq!(theme.rainbow_palette_colors(), vec![Color::DarkGray]);
+    }
+}
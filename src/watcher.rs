@@ -1,11 +1,19 @@
-use notify::{RecommendedWatcher, RecursiveMode, Watcher};
-use std::path::Path;
-use std::sync::mpsc;
+use notify::{Event as NotifyEvent, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
 use std::time::Duration;
 
+/// A single filesystem change, already classified by kind and resolved to
+/// the path it touched, rather than the generic "something changed" signal
+/// this used to hand back -- which forced every consumer to do a full
+/// rescan just to find out what happened.
+#[derive(Clone, Debug)]
 pub enum WatchEvent {
-    FileChanged,
-    #[allow(dead_code)] // Will be handled in Task 3
+    Created(PathBuf),
+    Modified(PathBuf),
+    Removed(PathBuf),
     Error(String),
 }
 
@@ -16,24 +24,50 @@ pub struct FileWatcher {
 impl FileWatcher {
     /// Create a new file watcher that monitors multiple paths recursively.
     /// All paths share a single watcher and channel.
+    ///
+    /// Raw `notify` events are coalesced by path for `debounce_duration`
+    /// before being sent: an editor's write-truncate-rewrite dance, or a
+    /// build tool touching the same file repeatedly, collapses into the
+    /// single most recent event for that path instead of fanning out one
+    /// event per underlying OS notification.
     pub fn new_multi(
         paths: &[&Path],
-        _debounce_duration: Duration,
+        debounce_duration: Duration,
     ) -> anyhow::Result<(Self, mpsc::Receiver<WatchEvent>)> {
         let (tx, rx) = mpsc::channel();
+        let pending: Arc<Mutex<HashMap<PathBuf, WatchEvent>>> = Arc::new(Mutex::new(HashMap::new()));
 
-        let sender = tx.clone();
+        let flush_pending = pending.clone();
+        let flush_tx = tx.clone();
+        thread::spawn(move || loop {
+            thread::sleep(debounce_duration);
+            let batch: Vec<WatchEvent> = flush_pending.lock().unwrap().drain().map(|(_, ev)| ev).collect();
+            for event in batch {
+                if flush_tx.send(event).is_err() {
+                    return;
+                }
+            }
+        });
+
+        let error_tx = tx;
         let mut watcher =
-            notify::recommended_watcher(move |res: Result<notify::Event, notify::Error>| {
-                match res {
-                    Ok(_event) => {
-                        // Send a generic "something changed" signal
-                        // The actual change detection is done by rescanning (like the Python version)
-                        let _ = sender.send(WatchEvent::FileChanged);
-                    }
-                    Err(e) => {
-                        let _ = sender.send(WatchEvent::Error(e.to_string()));
-                    }
+            notify::recommended_watcher(move |res: Result<NotifyEvent, notify::Error>| match res {
+                Ok(event) => {
+                    let Some(path) = event.paths.first().cloned() else {
+                        return;
+                    };
+                    let watch_event = match event.kind {
+                        EventKind::Create(_) => WatchEvent::Created(path.clone()),
+                        EventKind::Modify(_) => WatchEvent::Modified(path.clone()),
+                        EventKind::Remove(_) => WatchEvent::Removed(path.clone()),
+                        // Access and other bookkeeping events aren't changes
+                        // the viewer cares about.
+                        _ => return,
+                    };
+                    pending.lock().unwrap().insert(path, watch_event);
+                }
+                Err(e) => {
+                    let _ = error_tx.send(WatchEvent::Error(e.to_string()));
                 }
             })?;
 